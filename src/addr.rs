@@ -0,0 +1,168 @@
+//! Structured device addresses for radio configuration
+//!
+//! `DeviceAddress` provides typed, parseable and displayable addresses that
+//! convert to and from the address variants of `ConfigOption`.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+
+use crate::config::ConfigOption;
+
+/// Device address
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeviceAddress {
+    /// MAC address, displayed as `aa:bb:cc:dd:ee:ff`
+    MAC([u8; 6]),
+    /// IPv4 address, displayed in dotted decimal form
+    IPv4([u8; 4]),
+    /// IPv6 address, displayed in canonical form
+    IPv6([u8; 16]),
+    /// IEEE802.15.4 Short (16-bit) address, displayed as `0x1234`
+    Short(u16),
+    /// IEEE802.15.4 Long (64-bit) address, displayed as `0x0123456789abcdef`
+    Long(u64),
+}
+
+/// Error parsing a `DeviceAddress`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressParseError;
+
+impl fmt::Display for AddressParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid device address")
+    }
+}
+
+impl fmt::Display for DeviceAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceAddress::MAC(m) => write!(
+                f,
+                "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+                m[0], m[1], m[2], m[3], m[4], m[5]
+            ),
+            DeviceAddress::IPv4(a) => write!(f, "{}", Ipv4Addr::from(*a)),
+            DeviceAddress::IPv6(a) => write!(f, "{}", Ipv6Addr::from(*a)),
+            DeviceAddress::Short(a) => write!(f, "0x{:04x}", a),
+            DeviceAddress::Long(a) => write!(f, "0x{:016x}", a),
+        }
+    }
+}
+
+/// Parse addresses in the forms produced by `Display`
+///
+/// Hex (`0x` prefixed) addresses of up to 4 digits are parsed as short addresses,
+/// longer values as long addresses.
+impl FromStr for DeviceAddress {
+    type Err = AddressParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Short or long hex addresses
+        if let Some(h) = s.strip_prefix("0x") {
+            return match h.len() {
+                1..=4 => u16::from_str_radix(h, 16).map(DeviceAddress::Short),
+                5..=16 => u64::from_str_radix(h, 16).map(DeviceAddress::Long),
+                _ => return Err(AddressParseError),
+            }
+            .map_err(|_| AddressParseError);
+        }
+
+        // MAC addresses (six colon separated octets)
+        if s.split(':').count() == 6 && s.split(':').all(|p| p.len() == 2) {
+            let mut mac = [0u8; 6];
+            for (m, p) in mac.iter_mut().zip(s.split(':')) {
+                *m = u8::from_str_radix(p, 16).map_err(|_| AddressParseError)?;
+            }
+            return Ok(DeviceAddress::MAC(mac));
+        }
+
+        // IP addresses
+        if let Ok(a) = Ipv4Addr::from_str(s) {
+            return Ok(DeviceAddress::IPv4(a.octets()));
+        }
+        if let Ok(a) = Ipv6Addr::from_str(s) {
+            return Ok(DeviceAddress::IPv6(a.octets()));
+        }
+
+        Err(AddressParseError)
+    }
+}
+
+impl From<DeviceAddress> for ConfigOption {
+    fn from(a: DeviceAddress) -> Self {
+        match a {
+            DeviceAddress::MAC(v) => ConfigOption::MAC(v),
+            DeviceAddress::IPv4(v) => ConfigOption::IPv4(v),
+            DeviceAddress::IPv6(v) => ConfigOption::IPv6(v),
+            DeviceAddress::Short(v) => ConfigOption::ShortAddress(v),
+            DeviceAddress::Long(v) => ConfigOption::LongAddress(v),
+        }
+    }
+}
+
+/// Convert address options into a `DeviceAddress`, returning the original option on failure
+impl TryFrom<ConfigOption> for DeviceAddress {
+    type Error = ConfigOption;
+
+    fn try_from(o: ConfigOption) -> Result<Self, Self::Error> {
+        match o {
+            ConfigOption::MAC(v) => Ok(DeviceAddress::MAC(v)),
+            ConfigOption::IPv4(v) => Ok(DeviceAddress::IPv4(v)),
+            ConfigOption::IPv6(v) => Ok(DeviceAddress::IPv6(v)),
+            ConfigOption::ShortAddress(v) => Ok(DeviceAddress::Short(v)),
+            ConfigOption::LongAddress(v) => Ok(DeviceAddress::Long(v)),
+            _ => Err(o),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::string::ToString;
+
+    #[test]
+    fn address_display_parse() {
+        let addrs = [
+            (
+                DeviceAddress::MAC([0xaa, 0xbb, 0xcc, 0x00, 0x11, 0x22]),
+                "aa:bb:cc:00:11:22",
+            ),
+            (DeviceAddress::IPv4([192, 168, 1, 10]), "192.168.1.10"),
+            (
+                DeviceAddress::IPv6([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+                "fe80::1",
+            ),
+            (DeviceAddress::Short(0x1234), "0x1234"),
+            (
+                DeviceAddress::Long(0x0123_4567_89ab_cdef),
+                "0x0123456789abcdef",
+            ),
+        ];
+
+        for (a, s) in addrs.iter() {
+            assert_eq!(&a.to_string(), s);
+            assert_eq!(&DeviceAddress::from_str(s).unwrap(), a);
+        }
+
+        assert_eq!(DeviceAddress::from_str("aa:bb"), Err(AddressParseError));
+        assert_eq!(DeviceAddress::from_str("0x"), Err(AddressParseError));
+    }
+
+    #[test]
+    fn address_config_option() {
+        let a = DeviceAddress::Short(0xabcd);
+        let o: ConfigOption = a.into();
+        assert_eq!(o, ConfigOption::ShortAddress(0xabcd));
+        assert_eq!(DeviceAddress::try_from(o), Ok(a));
+
+        let o = ConfigOption::MTU(128);
+        assert_eq!(DeviceAddress::try_from(o.clone()), Err(o));
+    }
+}
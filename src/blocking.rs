@@ -6,21 +6,28 @@
 //! ## <https://github.com/rust-iot/radio-hal>
 //! ## Copyright 2020-2022 Ryan Kurte
 
+use core::convert::TryFrom;
 use core::fmt::Debug;
 use core::time::Duration;
 
 use embedded_hal::delay::DelayNs;
 
+#[cfg(not(feature = "defmt"))]
+use log::debug;
+
 #[cfg(feature = "defmt")]
 use defmt::debug;
 
 #[cfg(feature = "clap")]
 use clap::Parser;
 
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+
 #[cfg(feature = "std")]
 use std::string::ToString;
 
-use crate::{Receive, State, Transmit};
+use crate::{PreambleDetect, Receive, Rssi, State, Transmit};
 
 /// BlockingOptions for blocking radio functions
 #[derive(Clone, PartialEq, Debug)]
@@ -45,7 +52,53 @@ impl Default for BlockingOptions {
     }
 }
 
+/// Minimum poll interval for blocking and async operations
+///
+/// Shorter (or zero) intervals are clamped to this value to avoid busy spinning,
+/// as zero intervals would otherwise never accumulate towards a timeout.
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_micros(10);
+
+/// Delay for `us` microseconds, splitting delays exceeding `u32::MAX` microseconds
+/// into multiple `DelayNs::delay_us` calls
+pub(crate) fn delay_us_long<D: DelayNs + ?Sized>(delay: &mut D, mut us: u64) {
+    while us > u32::MAX as u64 {
+        delay.delay_us(u32::MAX);
+        us -= u32::MAX as u64;
+    }
+
+    delay.delay_us(us as u32);
+}
+
+/// Errors constructing blocking or async options
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OptionsError {
+    #[cfg_attr(feature = "thiserror", error("Poll interval must be non-zero"))]
+    ZeroPollInterval,
+}
+
+impl BlockingOptions {
+    /// Create blocking options, rejecting a zero poll interval
+    pub fn new(poll_interval: Duration, timeout: Duration) -> Result<Self, OptionsError> {
+        if poll_interval.is_zero() {
+            return Err(OptionsError::ZeroPollInterval);
+        }
+
+        Ok(Self {
+            poll_interval,
+            timeout,
+        })
+    }
+
+    /// Fetch the poll interval, clamped to `MIN_POLL_INTERVAL`
+    pub fn clamped_poll_interval(&self) -> Duration {
+        self.poll_interval.max(MIN_POLL_INTERVAL)
+    }
+}
+
 /// BlockingError wraps radio error type to provie a `Timeout` variant
+#[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -54,6 +107,10 @@ pub enum BlockingError<E> {
     Inner(E),
     #[cfg_attr(feature = "thiserror", error("Timeout"))]
     Timeout,
+    #[cfg_attr(feature = "thiserror", error("Payload exceeds radio MTU"))]
+    PayloadTooLarge,
+    #[cfg_attr(feature = "thiserror", error("Invalid options"))]
+    InvalidOptions,
 }
 
 impl<E> From<E> for BlockingError<E> {
@@ -95,6 +152,30 @@ pub trait BlockingTransmit<E: Debug> {
         data: &[u8],
         tx_options: BlockingOptions,
     ) -> Result<(), BlockingError<E>>;
+
+    /// Transmit a burst of frames, waiting for completion of each and
+    /// delaying for `gap` between frames
+    ///
+    /// On failure this stops and returns the index of the failed frame with the error
+    fn do_transmit_burst(
+        &mut self,
+        frames: &[&[u8]],
+        gap: Duration,
+        tx_options: BlockingOptions,
+    ) -> Result<(), (usize, BlockingError<E>)>;
+
+    /// Emulate a scheduled transmission by delaying until `when` before transmitting,
+    /// where `now` and `when` are radio-local times in microseconds
+    ///
+    /// Timing accuracy is limited by the delay implementation, prefer
+    /// `ScheduledTransmit` where supported by hardware.
+    fn do_transmit_at(
+        &mut self,
+        data: &[u8],
+        when: u64,
+        now: u64,
+        tx_options: BlockingOptions,
+    ) -> Result<(), BlockingError<E>>;
 }
 
 impl<T, E> BlockingTransmit<E> for T
@@ -107,6 +188,18 @@ where
         data: &[u8],
         tx_options: BlockingOptions,
     ) -> Result<(), BlockingError<E>> {
+        // Check payload fits within the radio MTU
+        if let Some(mtu) = T::MTU {
+            if data.len() > mtu {
+                debug!(
+                    "Blocking send payload exceeds MTU ({} > {})",
+                    data.len(),
+                    mtu
+                );
+                return Err(BlockingError::PayloadTooLarge);
+            }
+        }
+
         // Enter transmit mode
         self.start_transmit(data)?;
 
@@ -115,25 +208,58 @@ where
         loop {
             // Check for transmit complete
             if self.check_transmit()? {
-                #[cfg(any(feature = "log", feature = "defmt"))]
                 debug!("Blocking send complete");
                 break;
             }
 
             // Update poll time and timeout if overrun
-            c += tx_options.poll_interval.as_micros();
+            c += tx_options.clamped_poll_interval().as_micros();
             if c > t {
-                #[cfg(any(feature = "log", feature = "defmt"))]
                 debug!("Blocking send timeout");
                 return Err(BlockingError::Timeout);
             }
 
             // Wait for next poll
-            self.delay_us(tx_options.poll_interval.as_micros() as u32);
+            self.delay_us(tx_options.clamped_poll_interval().as_micros() as u32);
         }
 
         Ok(())
     }
+
+    fn do_transmit_burst(
+        &mut self,
+        frames: &[&[u8]],
+        gap: Duration,
+        tx_options: BlockingOptions,
+    ) -> Result<(), (usize, BlockingError<E>)> {
+        for (i, f) in frames.iter().enumerate() {
+            // Wait for inter-frame gap
+            if i > 0 {
+                delay_us_long(self, u64::try_from(gap.as_micros()).unwrap_or(u64::MAX));
+            }
+
+            // Transmit frame, returning the index on failure
+            self.do_transmit(f, tx_options.clone())
+                .map_err(|e| (i, e))?;
+        }
+
+        Ok(())
+    }
+
+    fn do_transmit_at(
+        &mut self,
+        data: &[u8],
+        when: u64,
+        now: u64,
+        tx_options: BlockingOptions,
+    ) -> Result<(), BlockingError<E>> {
+        // Wait for scheduled time, transmitting immediately if already passed
+        if when > now {
+            delay_us_long(self, when - now);
+        }
+
+        self.do_transmit(data, tx_options)
+    }
 }
 
 /// Blocking receive function implemented over `radio::Receive` using the provided `BlockingOptions`
@@ -180,6 +306,19 @@ pub trait BlockingReceive<I, E> {
         buff: &mut [u8],
         rx_options: BlockingOptions,
     ) -> Result<(usize, I), BlockingError<E>>;
+
+    /// Create an iterator over received packets, receiving into buffers of `buff_len` bytes
+    ///
+    /// Timeouts are yielded as `Err(BlockingError::Timeout)` items and reception continues,
+    /// other errors are yielded once before the iterator ends.
+    #[cfg(feature = "std")]
+    fn received_iter(
+        &mut self,
+        buff_len: usize,
+        rx_options: BlockingOptions,
+    ) -> ReceivedIter<'_, Self, I, E>
+    where
+        Self: Sized;
 }
 
 impl<T, I, E> BlockingReceive<I, E> for T
@@ -200,19 +339,189 @@ where
         let t = rx_options.timeout.as_micros();
         let mut c = 0;
         loop {
-            if self.check_receive(true)? {
+            if self.check_receive_continuous()? {
                 let (n, i) = self.get_received(buff)?;
                 return Ok((n, i));
             }
 
-            c += rx_options.poll_interval.as_micros();
+            c += rx_options.clamped_poll_interval().as_micros();
             if c > t {
-                #[cfg(any(feature = "log", feature = "defmt"))]
                 debug!("Blocking receive timeout");
                 return Err(BlockingError::Timeout);
             }
 
-            self.delay_us(rx_options.poll_interval.as_micros() as u32);
+            self.delay_us(rx_options.clamped_poll_interval().as_micros() as u32);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn received_iter(
+        &mut self,
+        buff_len: usize,
+        rx_options: BlockingOptions,
+    ) -> ReceivedIter<'_, Self, I, E> {
+        ReceivedIter {
+            radio: self,
+            buff_len,
+            options: rx_options,
+            done: false,
+            _inf: PhantomData,
+            _err: PhantomData,
+        }
+    }
+}
+
+/// Diagnostic information collected during a blocking receive
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReceiveDiagnostics {
+    /// Peak RSSI sampled during the receive window
+    pub peak_rssi: Option<i16>,
+    /// Number of RSSI samples taken
+    pub rssi_samples: u32,
+    /// Number of preamble detections observed while polling
+    /// (only collected by `do_receive_diag_preamble`)
+    pub preamble_detections: u32,
+}
+
+/// Blocking receive with channel diagnostics, implemented over `radio::Receive` and `radio::Rssi`
+///
+/// This samples RSSI at each poll interval so a timeout can be distinguished between
+/// an idle channel and energy that could not be demodulated.
+pub trait BlockingReceiveDiagnostics<I, E> {
+    /// Receive as with `BlockingReceive::do_receive`, updating `diag` with channel
+    /// information collected while waiting (including on timeout)
+    fn do_receive_diag(
+        &mut self,
+        buff: &mut [u8],
+        rx_options: BlockingOptions,
+        diag: &mut ReceiveDiagnostics,
+    ) -> Result<(usize, I), BlockingError<E>>;
+
+    /// Receive as with `do_receive_diag`, additionally counting preamble detections
+    /// reported by `radio::PreambleDetect` at each poll interval
+    fn do_receive_diag_preamble(
+        &mut self,
+        buff: &mut [u8],
+        rx_options: BlockingOptions,
+        diag: &mut ReceiveDiagnostics,
+    ) -> Result<(usize, I), BlockingError<E>>
+    where
+        Self: PreambleDetect<Error = E>;
+}
+
+impl<T, I, E> BlockingReceiveDiagnostics<I, E> for T
+where
+    T: Receive<Info = I, Error = E> + Rssi<Error = E> + DelayNs,
+    I: Debug,
+    E: Debug,
+{
+    fn do_receive_diag(
+        &mut self,
+        buff: &mut [u8],
+        rx_options: BlockingOptions,
+        diag: &mut ReceiveDiagnostics,
+    ) -> Result<(usize, I), BlockingError<E>> {
+        receive_diag(self, buff, rx_options, diag, |_, _| Ok(()))
+    }
+
+    fn do_receive_diag_preamble(
+        &mut self,
+        buff: &mut [u8],
+        rx_options: BlockingOptions,
+        diag: &mut ReceiveDiagnostics,
+    ) -> Result<(usize, I), BlockingError<E>>
+    where
+        Self: PreambleDetect<Error = E>,
+    {
+        // Count rising edges, as detection is latched until receive is restarted
+        let mut detected = false;
+        receive_diag(self, buff, rx_options, diag, |radio, diag| {
+            let d = radio.preamble_detected()?;
+            if d && !detected {
+                diag.preamble_detections += 1;
+            }
+            detected = d;
+            Ok(())
+        })
+    }
+}
+
+// Shared receive loop for `BlockingReceiveDiagnostics`, calling `sample` at each poll interval
+fn receive_diag<T, I, E>(
+    radio: &mut T,
+    buff: &mut [u8],
+    rx_options: BlockingOptions,
+    diag: &mut ReceiveDiagnostics,
+    mut sample: impl FnMut(&mut T, &mut ReceiveDiagnostics) -> Result<(), E>,
+) -> Result<(usize, I), BlockingError<E>>
+where
+    T: Receive<Info = I, Error = E> + Rssi<Error = E> + DelayNs,
+    E: Debug,
+{
+    // Start receive mode
+    radio.start_receive()?;
+
+    let t = rx_options.timeout.as_micros();
+    let mut c = 0;
+    loop {
+        if radio.check_receive_continuous()? {
+            let (n, i) = radio.get_received(buff)?;
+            return Ok((n, i));
+        }
+
+        // Sample channel RSSI
+        let rssi = radio.poll_rssi()?;
+        diag.rssi_samples += 1;
+        diag.peak_rssi = Some(diag.peak_rssi.map_or(rssi, |p| p.max(rssi)));
+
+        sample(radio, diag)?;
+
+        c += rx_options.clamped_poll_interval().as_micros();
+        if c > t {
+            debug!("Blocking receive timeout (peak rssi: {:?})", diag.peak_rssi);
+            return Err(BlockingError::Timeout);
+        }
+
+        radio.delay_us(rx_options.clamped_poll_interval().as_micros() as u32);
+    }
+}
+
+/// Iterator over received packets, see `BlockingReceive::received_iter`
+#[cfg(feature = "std")]
+pub struct ReceivedIter<'a, T, I, E> {
+    radio: &'a mut T,
+    buff_len: usize,
+    options: BlockingOptions,
+    done: bool,
+    _inf: PhantomData<I>,
+    _err: PhantomData<E>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, T, I, E> Iterator for ReceivedIter<'a, T, I, E>
+where
+    T: BlockingReceive<I, E>,
+{
+    type Item = Result<(Vec<u8>, I), BlockingError<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut buff = vec![0u8; self.buff_len];
+
+        match self.radio.do_receive(&mut buff, self.options.clone()) {
+            Ok((n, i)) => {
+                buff.truncate(n);
+                Some(Ok((buff, i)))
+            }
+            Err(BlockingError::Timeout) => Some(Err(BlockingError::Timeout)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
 }
@@ -253,15 +562,56 @@ where
             }
 
             // Timeout eventually
-            c += options.poll_interval.as_micros();
+            c += options.clamped_poll_interval().as_micros();
             if c > t {
-                #[cfg(any(feature = "log", feature = "defmt"))]
                 debug!("Blocking receive timeout");
                 return Err(BlockingError::Timeout);
             }
 
             // Delay before next loop
-            self.delay_us(options.poll_interval.as_micros() as u32);
+            self.delay_us(options.clamped_poll_interval().as_micros() as u32);
         }
     }
 }
+
+/// BlockingStateAfter runs a blocking operation then returns the radio to a specified state,
+/// centralising the "return to sleep after use" pattern
+pub trait BlockingStateAfter<S, E> {
+    /// Run `op`, then transition to `after` (if provided) using `set_state_checked`
+    ///
+    /// The transition is attempted even where `op` fails, in which case the operation error
+    /// is returned.
+    fn with_state_after<R>(
+        &mut self,
+        after: Option<S>,
+        options: BlockingOptions,
+        op: impl FnOnce(&mut Self) -> Result<R, BlockingError<E>>,
+    ) -> Result<R, BlockingError<E>>;
+}
+
+impl<T, S, E> BlockingStateAfter<S, E> for T
+where
+    T: State<State = S, Error = E> + DelayNs,
+    S: Debug + core::cmp::PartialEq + Copy,
+    E: Debug,
+{
+    fn with_state_after<R>(
+        &mut self,
+        after: Option<S>,
+        options: BlockingOptions,
+        op: impl FnOnce(&mut Self) -> Result<R, BlockingError<E>>,
+    ) -> Result<R, BlockingError<E>> {
+        let res = op(self);
+
+        if let Some(s) = after {
+            debug!("Setting state {:?} after operation", s);
+
+            let r = self.set_state_checked(s, options);
+            if res.is_ok() {
+                r?;
+            }
+        }
+
+        res
+    }
+}
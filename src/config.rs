@@ -1,5 +1,7 @@
 //! Config provides traits for standard radio configuration
 
+use core::time::Duration;
+
 /// Radio configuration options
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
@@ -23,6 +25,8 @@ pub enum ConfigOption {
     MTU(u16),
     /// Transmit power (dBm)
     TXPower(i16),
+    /// Power Amplifier (PA) selection for radios with multiple PAs
+    PaSelect(PaKind),
 
     /// Await Clear Channel before TX (if supported)
     AwaitCCA(bool),
@@ -32,6 +36,210 @@ pub enum ConfigOption {
     AutoAck(bool),
     /// Promiscuous mode (if supported) disables hardware address filtering
     Promiscuous(bool),
+
+    /// Sync words to match on receive (the first is used for transmit),
+    /// radios supporting only one sync word return `NotSupported` when given more than one
+    SyncWords(SyncWords),
+
+    /// Receive boosted gain (if supported) trades increased current draw for improved sensitivity
+    RxBoost(bool),
+
+    /// Preamble length for transmitted packets, in symbols for LoRa modulation
+    /// or bytes for FSK modulations (radios with a fixed preamble return `NotSupported`)
+    PreambleLength(u16),
+
+    /// Crystal load capacitance trim for per-unit frequency calibration
+    ///
+    /// The meaning of trim values is chip specific and should be treated as opaque,
+    /// however increasing values monotonically increase load capacitance (lowering frequency).
+    XtalTrim(u8),
+
+    /// Bit order for transmitted and received data (radios with a fixed order return `NotSupported`)
+    BitOrder(BitOrder),
+
+    /// Duty-cycled (wake-on-radio) receive, where supported in hardware
+    ///
+    /// The radio alternates between listening for `rx` and sleeping for `sleep`, extending
+    /// the listen window when a preamble is detected. Transmitters must use a preamble longer
+    /// than `rx + sleep` to be reliably detected.
+    RxDutyCycle { rx: Duration, sleep: Duration },
+
+    /// Data whitening mode (radios without whitening support return `NotSupported`,
+    /// as do radios unable to use arbitrary seeds with `WhiteningMode::Custom`)
+    Whitening(WhiteningMode),
+
+    /// Temperature Compensated Crystal Oscillator (TCXO) supply, for TCXO-equipped modules
+    ///
+    /// The radio powers the TCXO at `voltage_mv` and waits `startup` for the oscillator to
+    /// stabilise before use. Radios without a TCXO supply return `NotSupported`.
+    Tcxo { voltage_mv: u16, startup: Duration },
+
+    /// Hardware automatic retransmission (if supported), retrying unacknowledged packets
+    /// up to `count` times with `delay` between attempts (a count of zero disables retransmission)
+    AutoRetransmit { count: u8, delay: Duration },
+
+    /// Hardware CRC generation on transmit and checking on receive (if supported)
+    ///
+    /// With CRC disabled received frames are not checked, so `Receive::check_receive` will
+    /// not report or restart on CRC errors and integrity must be checked by the application
+    /// (for example with a software FCS).
+    Crc(bool),
+
+    /// Data slicer threshold mode for OOK / ASK reception (radios without a configurable
+    /// slicer return `NotSupported`)
+    SlicerThreshold(SlicerMode),
+
+    /// Automatic crystal temperature compensation (if supported), correcting frequency
+    /// drift over temperature using the radio's internal temperature sensor
+    TempCompensation(bool),
+
+    /// Receiver bandwidth override in Hz, independent of the nominal channel bandwidth
+    /// (for example to tolerate transmitter frequency offsets)
+    RxBandwidth(u32),
+
+    /// Over-the-air line coding, for interoperability with legacy or proprietary links
+    /// (radios without on-chip line coding return `NotSupported`)
+    LineCoding(LineCoding),
+
+    /// Centre frequency in Hz
+    ///
+    /// Radios reporting `Configure::frequency_range` should reject values outside
+    /// this with `ConfigError::OutOfRange`, see `Configure::set_frequency`.
+    Frequency(u32),
+
+    /// Modulation (channel) bandwidth in Hz, see `RxBandwidth` for receiver bandwidth overrides
+    Bandwidth(u32),
+
+    /// LoRa spreading factor (radios without LoRa modulation return `NotSupported`)
+    SpreadingFactor(u8),
+}
+
+/// Maximum number of sync words in a `SyncWords` set
+pub const MAX_SYNC_WORDS: usize = 4;
+
+/// Maximum length of each sync word in a `SyncWords` set
+pub const MAX_SYNC_WORD_LEN: usize = 8;
+
+/// Fixed capacity set of sync words for `ConfigOption::SyncWords`
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct SyncWords {
+    words: [[u8; MAX_SYNC_WORD_LEN]; MAX_SYNC_WORDS],
+    lens: [u8; MAX_SYNC_WORDS],
+    count: usize,
+}
+
+impl SyncWords {
+    /// Create a sync word set from the provided slices
+    ///
+    /// Returns `None` if more than `MAX_SYNC_WORDS` words are provided or any word
+    /// is longer than `MAX_SYNC_WORD_LEN`
+    pub fn from_slices(words: &[&[u8]]) -> Option<Self> {
+        if words.len() > MAX_SYNC_WORDS {
+            return None;
+        }
+
+        let mut s = Self::default();
+        for w in words {
+            if w.len() > MAX_SYNC_WORD_LEN {
+                return None;
+            }
+
+            s.words[s.count][..w.len()].copy_from_slice(w);
+            s.lens[s.count] = w.len() as u8;
+            s.count += 1;
+        }
+
+        Some(s)
+    }
+
+    /// Number of sync words in the set
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Iterate over sync words in the set
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.words[..self.count]
+            .iter()
+            .zip(self.lens.iter())
+            .map(|(w, l)| &w[..*l as usize])
+    }
+}
+
+/// Power Amplifier (PA) selection
+///
+/// The valid range for `Power::set_power` depends on the selected PA
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaKind {
+    /// Low power PA, for reduced current draw
+    LowPower,
+    /// High power PA, for maximum output power
+    HighPower,
+    /// Automatic PA selection based on requested power
+    Auto,
+}
+
+/// Packet framing configuration, for diagnosing mismatched peers
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PacketConfig {
+    /// Hardware CRC enabled
+    pub crc: bool,
+    /// Data whitening enabled
+    pub whitening: bool,
+    /// Variable length packets (explicit length header) enabled
+    pub variable_length: bool,
+}
+
+/// Data whitening mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WhiteningMode {
+    /// Whitening disabled
+    Disabled,
+    /// Whitening with the radio's default polynomial and seed
+    Standard,
+    /// Whitening with a custom LFSR polynomial and seed, for interoperating with
+    /// systems using non-standard whitening
+    Custom { poly: u16, seed: u16 },
+}
+
+/// Data slicer threshold mode for OOK / ASK demodulation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SlicerMode {
+    /// Threshold tracks the peak signal level
+    Peak,
+    /// Fixed threshold in radio-specific units
+    Fixed(i16),
+    /// Threshold tracks the average signal level
+    Average,
+}
+
+/// Line coding applied to the over-the-air bitstream
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCoding {
+    /// Non-return-to-zero, data is transmitted unmodified
+    Nrz,
+    /// Manchester encoding, each bit is transmitted as a pair of chips
+    Manchester,
+    /// Inverted NRZ, each bit is transmitted inverted
+    Inverted,
+}
+
+/// Bit order for over-the-air data
+///
+/// This applies to the sync word, length / address fields and payload bytes,
+/// so peers must be configured with the same order for sync words to match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BitOrder {
+    /// Most significant bit of each byte transmitted first
+    MsbFirst,
+    /// Least significant bit of each byte transmitted first
+    LsbFirst,
 }
 
 /// Radio configuration errors
@@ -39,10 +247,15 @@ pub enum ConfigOption {
 /// with radio-specific errors passed through the Other(E) field.
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ConfigError<E> {
     /// Configuration option not supported
     NotSupported,
 
+    /// Configuration value outside the range supported by the radio
+    /// (for example a frequency outside the radio's band)
+    OutOfRange,
+
     /// Other (device, non-configuration errors)
     Other(E),
 }
@@ -60,4 +273,509 @@ pub trait Configure {
     /// This will overwrite the value of the provided option enum
     /// Returns Ok(true) on successful get, Ok(false) for unsupported options, Err(Self::Error) for errors
     fn get_option(&mut self, o: &mut ConfigOption) -> Result<(), ConfigError<Self::Error>>;
+
+    /// Apply a set of configuration options in order
+    ///
+    /// On failure this stops and returns the index of the failed option with the error.
+    /// `ConfigOption::Frequency` values are checked with `set_frequency`.
+    fn apply_all(
+        &mut self,
+        options: &[ConfigOption],
+    ) -> Result<(), (usize, ConfigError<Self::Error>)> {
+        for (i, o) in options.iter().enumerate() {
+            let r = match o {
+                ConfigOption::Frequency(f) => self.set_frequency(*f),
+                _ => self.set_option(o),
+            };
+            r.map_err(|e| (i, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the supported (minimum, maximum) centre frequency range in Hz, if known
+    fn frequency_range(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Set the centre frequency in Hz, returning `ConfigError::OutOfRange` without
+    /// applying the frequency where this is outside of `frequency_range`
+    fn set_frequency(&mut self, hz: u32) -> Result<(), ConfigError<Self::Error>> {
+        if let Some((min, max)) = self.frequency_range() {
+            if hz < min || hz > max {
+                return Err(ConfigError::OutOfRange);
+            }
+        }
+
+        self.set_option(&ConfigOption::Frequency(hz))
+    }
+
+    /// Fetch the effective MTU, the maximum payload length given the current configuration
+    ///
+    /// This may be lower than a configured `ConfigOption::MTU`, for example due to LoRa
+    /// implicit header limits or FSK packet length field widths. The default implementation
+    /// returns the value of `ConfigOption::MTU`, radios with configuration dependent limits
+    /// should override this.
+    fn effective_mtu(&mut self) -> Result<u16, ConfigError<Self::Error>> {
+        let mut o = ConfigOption::MTU(0);
+        self.get_option(&mut o)?;
+
+        match o {
+            ConfigOption::MTU(v) => Ok(v),
+            _ => Err(ConfigError::NotSupported),
+        }
+    }
+
+    /// Snapshot the current radio configuration, for later use with `restore`
+    ///
+    /// This reads each of `ConfigSnapshot::templates` with `get_option`, with options returning
+    /// `ConfigError::NotSupported` skipped (and other errors returned).
+    fn snapshot(&mut self) -> Result<ConfigSnapshot, ConfigError<Self::Error>> {
+        let mut snapshot = ConfigSnapshot::default();
+
+        for (i, t) in ConfigSnapshot::templates().iter().enumerate() {
+            let mut o = t.clone();
+            match self.get_option(&mut o) {
+                Ok(()) => snapshot.options[i] = Some(o),
+                Err(ConfigError::NotSupported) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Restore a configuration snapshot taken with `snapshot`, applying options in order
+    ///
+    /// Options returning `ConfigError::NotSupported` (for example read-only options)
+    /// are skipped, with other errors returned.
+    fn restore(&mut self, snapshot: &ConfigSnapshot) -> Result<(), ConfigError<Self::Error>> {
+        for o in snapshot.iter() {
+            match self.set_option(o) {
+                Ok(()) | Err(ConfigError::NotSupported) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of options read by `Configure::snapshot`
+pub const SNAPSHOT_OPTIONS: usize = 30;
+
+/// Radio configuration snapshot from `Configure::snapshot`, containing the
+/// value of each supported option
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigSnapshot {
+    options: [Option<ConfigOption>; SNAPSHOT_OPTIONS],
+}
+
+impl Default for ConfigSnapshot {
+    fn default() -> Self {
+        const NONE: Option<ConfigOption> = None;
+        Self {
+            options: [NONE; SNAPSHOT_OPTIONS],
+        }
+    }
+}
+
+impl ConfigSnapshot {
+    /// Fetch the options (with placeholder values) read when taking a snapshot
+    ///
+    /// Options are listed in the order they are restored. New `ConfigOption` variants must be
+    /// added here, which is checked by the `snapshot_templates` test.
+    pub fn templates() -> [ConfigOption; SNAPSHOT_OPTIONS] {
+        [
+            ConfigOption::MAC([0u8; 6]),
+            ConfigOption::IPv4([0u8; 4]),
+            ConfigOption::IPv6([0u8; 16]),
+            ConfigOption::ShortAddress(0),
+            ConfigOption::LongAddress(0),
+            ConfigOption::PAN(0),
+            ConfigOption::MTU(0),
+            // PA selection precedes TX power, as the valid power range depends on the PA
+            ConfigOption::PaSelect(PaKind::Auto),
+            ConfigOption::TXPower(0),
+            ConfigOption::AwaitCCA(false),
+            ConfigOption::CCAThreshold(0),
+            ConfigOption::AutoAck(false),
+            ConfigOption::Promiscuous(false),
+            ConfigOption::SyncWords(SyncWords::default()),
+            ConfigOption::RxBoost(false),
+            ConfigOption::PreambleLength(0),
+            ConfigOption::XtalTrim(0),
+            ConfigOption::BitOrder(BitOrder::MsbFirst),
+            ConfigOption::RxDutyCycle {
+                rx: Duration::from_secs(0),
+                sleep: Duration::from_secs(0),
+            },
+            ConfigOption::Whitening(WhiteningMode::Disabled),
+            ConfigOption::Tcxo {
+                voltage_mv: 0,
+                startup: Duration::from_secs(0),
+            },
+            ConfigOption::AutoRetransmit {
+                count: 0,
+                delay: Duration::from_secs(0),
+            },
+            ConfigOption::Crc(false),
+            ConfigOption::SlicerThreshold(SlicerMode::Peak),
+            ConfigOption::TempCompensation(false),
+            ConfigOption::RxBandwidth(0),
+            ConfigOption::LineCoding(LineCoding::Nrz),
+            ConfigOption::Frequency(0),
+            ConfigOption::Bandwidth(0),
+            ConfigOption::SpreadingFactor(0),
+        ]
+    }
+
+    /// Iterate over the options captured in the snapshot
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigOption> {
+        self.options.iter().filter_map(|o| o.as_ref())
+    }
+
+    /// Fetch the number of options captured in the snapshot
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Check whether the snapshot is empty (no options were supported)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// LoRaWAN public network sync word
+const LORAWAN_SYNC_WORD: u8 = 0x34;
+
+/// Regional parameter presets for LoRaWAN regions
+///
+/// These encode the regional defaults expressible as `ConfigOption`s for use with
+/// `Configure::apply_all`, along with regional limits for applications to enforce.
+/// Transmit power is not set by the presets, as the permitted conducted power depends
+/// on antenna gain, see `max_eirp_dbm` and `validate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegionalPreset {
+    /// Europe 863-870 MHz
+    EU868,
+    /// United States 902-928 MHz
+    US915,
+    /// Asia 923 MHz
+    AS923,
+}
+
+impl RegionalPreset {
+    /// Fetch configuration options for the region
+    ///
+    /// This tunes to the first default channel using SF7 / 125 kHz, a data rate
+    /// available on the default channels of each region.
+    pub fn options(&self) -> [ConfigOption; 5] {
+        [
+            ConfigOption::Frequency(self.default_channels_hz()[0]),
+            ConfigOption::Bandwidth(125_000),
+            ConfigOption::SpreadingFactor(7),
+            ConfigOption::SyncWords(SyncWords::from_slices(&[&[LORAWAN_SYNC_WORD][..]]).unwrap()),
+            ConfigOption::PreambleLength(8),
+        ]
+    }
+
+    /// Default maximum EIRP in dBm
+    pub fn max_eirp_dbm(&self) -> i8 {
+        match self {
+            RegionalPreset::EU868 => 16,
+            RegionalPreset::US915 => 30,
+            RegionalPreset::AS923 => 16,
+        }
+    }
+
+    /// Maximum transmit duty cycle in percent, where regulated
+    pub fn duty_cycle_pct(&self) -> Option<f32> {
+        match self {
+            RegionalPreset::EU868 => Some(1.0),
+            RegionalPreset::US915 => None,
+            RegionalPreset::AS923 => None,
+        }
+    }
+
+    /// Default uplink channel centre frequencies in Hz
+    pub fn default_channels_hz(&self) -> &'static [u32] {
+        match self {
+            RegionalPreset::EU868 => &[868_100_000, 868_300_000, 868_500_000],
+            RegionalPreset::US915 => &[
+                902_300_000,
+                902_500_000,
+                902_700_000,
+                902_900_000,
+                903_100_000,
+                903_300_000,
+                903_500_000,
+                903_700_000,
+            ],
+            RegionalPreset::AS923 => &[923_200_000, 923_400_000],
+        }
+    }
+}
+
+/// Configuration validation errors, returned by `validate`
+///
+/// Indices refer to positions in the validated option slice.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ValidationError {
+    /// The same option is set more than once with different values
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Option {second} conflicts with earlier option {first}")
+    )]
+    Duplicate { first: usize, second: usize },
+
+    /// Transmit power exceeds the regional limit
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Option {index} power {power} dBm exceeds limit of {max} dBm")
+    )]
+    PowerAboveLimit { index: usize, power: i16, max: i16 },
+
+    /// Hardware acknowledgement or retransmission is enabled with CRC disabled,
+    /// acknowledgements are only sent for frames passing CRC checks
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Option {index} requires CRC, disabled by option {crc}")
+    )]
+    RequiresCrc { index: usize, crc: usize },
+
+    /// MTU exceeds the maximum payload length of the configured modulation
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Option {index} MTU {mtu} exceeds modulation limit of {max}, set by option {modulation}")
+    )]
+    MtuAboveModulationLimit {
+        index: usize,
+        mtu: u16,
+        max: u16,
+        modulation: usize,
+    },
+}
+
+/// Maximum LoRa payload length (limited by the 8-bit header length field)
+const LORA_MAX_PAYLOAD: u16 = 255;
+
+/// Validate a set of configuration options prior to applying them
+///
+/// This checks for conflicting duplicate options, transmit power above the limit of the
+/// provided region (if any), hardware acknowledgement / retransmission with CRC disabled,
+/// and an MTU exceeding the payload limit of the configured modulation, returning the first
+/// error found.
+///
+/// There are no generic modulation or capability types, so modulation is determined from the
+/// modulation options in `opts` (`ConfigOption::SpreadingFactor` selecting LoRa).
+pub fn validate(
+    opts: &[ConfigOption],
+    region: Option<RegionalPreset>,
+) -> Result<(), ValidationError> {
+    for (i, o) in opts.iter().enumerate() {
+        let first = opts[..i]
+            .iter()
+            .position(|p| core::mem::discriminant(p) == core::mem::discriminant(o) && p != o);
+        if let Some(first) = first {
+            return Err(ValidationError::Duplicate { first, second: i });
+        }
+
+        if let (ConfigOption::TXPower(power), Some(r)) = (o, region) {
+            let max = r.max_eirp_dbm() as i16;
+            if *power > max {
+                return Err(ValidationError::PowerAboveLimit {
+                    index: i,
+                    power: *power,
+                    max,
+                });
+            }
+        }
+    }
+
+    let crc = opts
+        .iter()
+        .position(|o| matches!(o, ConfigOption::Crc(false)));
+    let ack = opts.iter().position(|o| {
+        matches!(
+            o,
+            ConfigOption::AutoAck(true) | ConfigOption::AutoRetransmit { count: 1.., .. }
+        )
+    });
+    if let (Some(crc), Some(index)) = (crc, ack) {
+        return Err(ValidationError::RequiresCrc { index, crc });
+    }
+
+    let lora = opts
+        .iter()
+        .position(|o| matches!(o, ConfigOption::SpreadingFactor(_)));
+    let mtu = opts.iter().enumerate().find_map(|(i, o)| match o {
+        ConfigOption::MTU(m) => Some((i, *m)),
+        _ => None,
+    });
+    if let (Some(modulation), Some((index, mtu))) = (lora, mtu) {
+        if mtu > LORA_MAX_PAYLOAD {
+            return Err(ValidationError::MtuAboveModulationLimit {
+                index,
+                mtu,
+                max: LORA_MAX_PAYLOAD,
+                modulation,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_templates() {
+        let templates = ConfigSnapshot::templates();
+
+        for (i, t) in templates.iter().enumerate() {
+            // Exhaustive so new options fail to compile until added here and to `templates`
+            match t {
+                ConfigOption::MAC(_)
+                | ConfigOption::IPv4(_)
+                | ConfigOption::IPv6(_)
+                | ConfigOption::ShortAddress(_)
+                | ConfigOption::LongAddress(_)
+                | ConfigOption::PAN(_)
+                | ConfigOption::MTU(_)
+                | ConfigOption::TXPower(_)
+                | ConfigOption::PaSelect(_)
+                | ConfigOption::AwaitCCA(_)
+                | ConfigOption::CCAThreshold(_)
+                | ConfigOption::AutoAck(_)
+                | ConfigOption::Promiscuous(_)
+                | ConfigOption::SyncWords(_)
+                | ConfigOption::RxBoost(_)
+                | ConfigOption::PreambleLength(_)
+                | ConfigOption::XtalTrim(_)
+                | ConfigOption::BitOrder(_)
+                | ConfigOption::RxDutyCycle { .. }
+                | ConfigOption::Whitening(_)
+                | ConfigOption::Tcxo { .. }
+                | ConfigOption::AutoRetransmit { .. }
+                | ConfigOption::Crc(_)
+                | ConfigOption::SlicerThreshold(_)
+                | ConfigOption::TempCompensation(_)
+                | ConfigOption::RxBandwidth(_)
+                | ConfigOption::LineCoding(_)
+                | ConfigOption::Frequency(_)
+                | ConfigOption::Bandwidth(_)
+                | ConfigOption::SpreadingFactor(_) => (),
+            }
+
+            // Each option is read once
+            assert!(templates[i + 1..]
+                .iter()
+                .all(|o| core::mem::discriminant(o) != core::mem::discriminant(t)));
+        }
+
+        // PA selection is restored prior to TX power
+        let pos = |o: &ConfigOption| {
+            templates
+                .iter()
+                .position(|t| core::mem::discriminant(t) == core::mem::discriminant(o))
+        };
+        assert!(pos(&ConfigOption::PaSelect(PaKind::Auto)) < pos(&ConfigOption::TXPower(0)));
+    }
+
+    #[test]
+    fn validate_ok() {
+        let opts = RegionalPreset::EU868.options();
+        assert_eq!(validate(&opts, Some(RegionalPreset::EU868)), Ok(()));
+
+        // Repeated options with the same value are permitted
+        let opts = [ConfigOption::Crc(true), ConfigOption::Crc(true)];
+        assert_eq!(validate(&opts, None), Ok(()));
+    }
+
+    #[test]
+    fn validate_duplicate() {
+        let opts = [
+            ConfigOption::MTU(64),
+            ConfigOption::AutoAck(true),
+            ConfigOption::MTU(128),
+        ];
+        assert_eq!(
+            validate(&opts, None),
+            Err(ValidationError::Duplicate {
+                first: 0,
+                second: 2
+            })
+        );
+    }
+
+    #[test]
+    fn validate_power_limit() {
+        let opts = [ConfigOption::MTU(64), ConfigOption::TXPower(20)];
+        assert_eq!(validate(&opts, None), Ok(()));
+        assert_eq!(validate(&opts, Some(RegionalPreset::US915)), Ok(()));
+        assert_eq!(
+            validate(&opts, Some(RegionalPreset::EU868)),
+            Err(ValidationError::PowerAboveLimit {
+                index: 1,
+                power: 20,
+                max: 16
+            })
+        );
+    }
+
+    #[test]
+    fn validate_requires_crc() {
+        let opts = [ConfigOption::AutoAck(true), ConfigOption::Crc(false)];
+        assert_eq!(
+            validate(&opts, None),
+            Err(ValidationError::RequiresCrc { index: 0, crc: 1 })
+        );
+
+        let opts = [
+            ConfigOption::Crc(false),
+            ConfigOption::AutoRetransmit {
+                count: 3,
+                delay: Duration::from_millis(10),
+            },
+        ];
+        assert_eq!(
+            validate(&opts, None),
+            Err(ValidationError::RequiresCrc { index: 1, crc: 0 })
+        );
+
+        // Retransmission disabled does not require CRC
+        let opts = [
+            ConfigOption::Crc(false),
+            ConfigOption::AutoRetransmit {
+                count: 0,
+                delay: Duration::from_millis(10),
+            },
+        ];
+        assert_eq!(validate(&opts, None), Ok(()));
+    }
+    #[test]
+    fn validate_mtu_modulation() {
+        let opts = [ConfigOption::SpreadingFactor(9), ConfigOption::MTU(255)];
+        assert_eq!(validate(&opts, None), Ok(()));
+
+        // Without LoRa modulation configured the MTU is not limited
+        let opts = [ConfigOption::MTU(1024)];
+        assert_eq!(validate(&opts, None), Ok(()));
+
+        let opts = [ConfigOption::MTU(1024), ConfigOption::SpreadingFactor(9)];
+        assert_eq!(
+            validate(&opts, None),
+            Err(ValidationError::MtuAboveModulationLimit {
+                index: 0,
+                mtu: 1024,
+                max: 255,
+                modulation: 1
+            })
+        );
+    }
 }
@@ -0,0 +1,163 @@
+//! Software frame check sequence (FCS) helpers for radios without hardware CRC
+//!
+//! These are `no_std` compatible for use on-device.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+/// CRC algorithms supported for software frame check sequences
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CrcKind {
+    /// CRC-16-CCITT (poly 0x1021, init 0xFFFF), 2 byte FCS
+    Crc16Ccitt,
+    /// CRC-32 (IEEE 802.3), 4 byte FCS
+    Crc32,
+}
+
+impl CrcKind {
+    /// Length of the FCS in bytes
+    pub fn fcs_len(&self) -> usize {
+        match self {
+            CrcKind::Crc16Ccitt => 2,
+            CrcKind::Crc32 => 4,
+        }
+    }
+
+    /// Compute the CRC over the provided data
+    pub fn compute(&self, data: &[u8]) -> u32 {
+        match self {
+            CrcKind::Crc16Ccitt => {
+                let mut crc = 0xFFFFu16;
+                for b in data {
+                    crc ^= (*b as u16) << 8;
+                    for _ in 0..8 {
+                        crc = match crc & 0x8000 != 0 {
+                            true => (crc << 1) ^ 0x1021,
+                            false => crc << 1,
+                        };
+                    }
+                }
+                crc as u32
+            }
+            CrcKind::Crc32 => {
+                let mut crc = 0xFFFF_FFFFu32;
+                for b in data {
+                    crc ^= *b as u32;
+                    for _ in 0..8 {
+                        crc = match crc & 1 != 0 {
+                            true => (crc >> 1) ^ 0xEDB8_8320,
+                            false => crc >> 1,
+                        };
+                    }
+                }
+                !crc
+            }
+        }
+    }
+}
+
+/// Software Frame Check Sequence (FCS) helper for radios without hardware CRC
+///
+/// The FCS is appended to the payload in network (big-endian) byte order.
+pub struct Fcs;
+
+impl Fcs {
+    /// Copy `data` into `out` and append the FCS, returning the total frame length
+    ///
+    /// `out` must be at least `data.len() + poly.fcs_len()` bytes long
+    pub fn append(data: &[u8], out: &mut [u8], poly: CrcKind) -> usize {
+        let n = data.len();
+        out[..n].copy_from_slice(data);
+
+        let crc = poly.compute(data);
+        let m = n + poly.fcs_len();
+        match poly {
+            CrcKind::Crc16Ccitt => out[n..m].copy_from_slice(&(crc as u16).to_be_bytes()),
+            CrcKind::Crc32 => out[n..m].copy_from_slice(&crc.to_be_bytes()),
+        }
+
+        m
+    }
+
+    /// Verify the FCS at the end of the provided frame
+    ///
+    /// Returns false if the frame is too short to contain an FCS
+    pub fn verify(frame: &[u8], poly: CrcKind) -> bool {
+        if frame.len() < poly.fcs_len() {
+            return false;
+        }
+
+        let (data, fcs) = frame.split_at(frame.len() - poly.fcs_len());
+        let expected = match poly {
+            CrcKind::Crc16Ccitt => u16::from_be_bytes([fcs[0], fcs[1]]) as u32,
+            CrcKind::Crc32 => u32::from_be_bytes([fcs[0], fcs[1], fcs[2], fcs[3]]),
+        };
+
+        poly.compute(data) == expected
+    }
+}
+
+/// Compute the IEEE 802.15.4 FCS (CRC-16 ITU-T, poly 0x1021 reflected, init 0) over the payload
+pub fn ieee802154_fcs(payload: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for b in payload {
+        crc ^= *b as u16;
+        for _ in 0..8 {
+            crc = match crc & 1 != 0 {
+                true => (crc >> 1) ^ 0x8408,
+                false => crc >> 1,
+            };
+        }
+    }
+    crc
+}
+
+/// Verify the IEEE 802.15.4 FCS (transmitted little-endian) at the end of a frame
+///
+/// Returns false if the frame is too short to contain an FCS
+pub fn ieee802154_fcs_verify(frame: &[u8]) -> bool {
+    if frame.len() < 2 {
+        return false;
+    }
+
+    let (payload, fcs) = frame.split_at(frame.len() - 2);
+    ieee802154_fcs(payload) == u16::from_le_bytes([fcs[0], fcs[1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fcs_check_values() {
+        assert_eq!(CrcKind::Crc16Ccitt.compute(b"123456789"), 0x29B1);
+        assert_eq!(CrcKind::Crc32.compute(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn ieee802154_fcs_check() {
+        assert_eq!(ieee802154_fcs(b"123456789"), 0x2189);
+
+        // Ack frame with FCS
+        let frame = [0x02, 0x00, 0x2a, 0xe0, 0x3b];
+        assert!(ieee802154_fcs_verify(&frame));
+        assert!(!ieee802154_fcs_verify(&frame[..4]));
+        assert!(!ieee802154_fcs_verify(&[0x00]));
+    }
+
+    #[test]
+    fn fcs_append_verify() {
+        for poly in [CrcKind::Crc16Ccitt, CrcKind::Crc32] {
+            let mut buff = [0u8; 16];
+            let n = Fcs::append(&[0xaa, 0xbb, 0xcc], &mut buff, poly);
+
+            assert_eq!(n, 3 + poly.fcs_len());
+            assert!(Fcs::verify(&buff[..n], poly));
+
+            buff[1] ^= 0x01;
+            assert!(!Fcs::verify(&buff[..n], poly));
+        }
+
+        assert!(!Fcs::verify(&[0xaa], CrcKind::Crc32));
+    }
+}
@@ -0,0 +1,278 @@
+//! Fragmentation and reassembly of payloads larger than the radio MTU
+//!
+//! Fragments are prefixed with a two byte header of fragment index and total fragment
+//! count, allowing up to 255 fragments per payload. Reassembly uses a fixed buffer
+//! so this is `no_std` compatible.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+/// Length of the fragment header (index and total)
+pub const FRAGMENT_HEADER_LEN: usize = 2;
+
+/// Maximum number of fragments per payload
+pub const MAX_FRAGMENTS: usize = u8::MAX as usize;
+
+/// Fragment of a larger payload
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fragment<'a> {
+    /// Index of this fragment
+    pub index: u8,
+    /// Total number of fragments
+    pub total: u8,
+    /// Fragment payload
+    pub data: &'a [u8],
+}
+
+impl<'a> Fragment<'a> {
+    /// Encode the fragment into the provided buffer, returning the encoded length
+    pub fn encode(&self, buff: &mut [u8]) -> usize {
+        let n = FRAGMENT_HEADER_LEN + self.data.len();
+
+        buff[0] = self.index;
+        buff[1] = self.total;
+        buff[FRAGMENT_HEADER_LEN..n].copy_from_slice(self.data);
+
+        n
+    }
+
+    /// Decode a fragment from a received frame
+    pub fn decode(frame: &'a [u8]) -> Option<Self> {
+        if frame.len() < FRAGMENT_HEADER_LEN || frame[0] >= frame[1] {
+            return None;
+        }
+
+        Some(Self {
+            index: frame[0],
+            total: frame[1],
+            data: &frame[FRAGMENT_HEADER_LEN..],
+        })
+    }
+}
+
+/// Fragmenter splits payloads into MTU sized fragments
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Fragmenter;
+
+impl Fragmenter {
+    /// Create a new fragmenter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Split `data` into fragments which, including the fragment header, fit within `mtu`
+    ///
+    /// Panics if `mtu` does not exceed the fragment header length or if more
+    /// than `MAX_FRAGMENTS` fragments would be required.
+    pub fn fragment<'a>(
+        &self,
+        data: &'a [u8],
+        mtu: usize,
+    ) -> impl Iterator<Item = Fragment<'a>> + 'a {
+        assert!(
+            mtu > FRAGMENT_HEADER_LEN,
+            "mtu too small for fragment header"
+        );
+
+        let chunk = mtu - FRAGMENT_HEADER_LEN;
+        let total = data.len().div_ceil(chunk).max(1);
+        assert!(
+            total <= MAX_FRAGMENTS,
+            "payload requires too many fragments"
+        );
+
+        (0..total).map(move |i| Fragment {
+            index: i as u8,
+            total: total as u8,
+            data: &data[(i * chunk).min(data.len())..((i + 1) * chunk).min(data.len())],
+        })
+    }
+}
+
+/// Errors during fragment reassembly
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReassemblyError {
+    /// Frame is not a valid fragment
+    Malformed,
+    /// Fragment does not match the payload being reassembled
+    Mismatch,
+    /// Payload exceeds the reassembly buffer
+    Overflow,
+}
+
+/// Reassembler collects fragments into a fixed `N` byte buffer
+///
+/// Reassembly state is reset once a payload completes. As fragments do not carry a payload
+/// identifier, a fragment with a different total, or with an index that has already been
+/// received, is treated as the start of a new payload and discards any partial payload.
+pub struct Reassembler<const N: usize> {
+    buff: [u8; N],
+    chunk: usize,
+    total: Option<u8>,
+    len: usize,
+    received: [u32; 8],
+}
+
+impl<const N: usize> Reassembler<N> {
+    /// Create a reassembler for fragments produced with the provided `mtu`
+    pub fn new(mtu: usize) -> Self {
+        Self {
+            buff: [0u8; N],
+            chunk: mtu.saturating_sub(FRAGMENT_HEADER_LEN),
+            total: None,
+            len: 0,
+            received: [0; 8],
+        }
+    }
+
+    /// Reset reassembly state, discarding received fragments
+    pub fn reset(&mut self) {
+        self.total = None;
+        self.len = 0;
+        self.received = [0; 8];
+    }
+
+    /// Push a received frame, returning the reassembled payload once all fragments are received
+    pub fn push(&mut self, frame: &[u8]) -> Result<Option<&[u8]>, ReassemblyError> {
+        let f = Fragment::decode(frame).ok_or(ReassemblyError::Malformed)?;
+
+        // Start a new payload if the fragment does not belong to the current one
+        if self.total.is_some() && (self.total != Some(f.total) || self.has(f.index)) {
+            self.reset();
+        }
+
+        // Non-final fragments must be full size
+        let last = f.index == f.total - 1;
+        if (!last && f.data.len() != self.chunk) || f.data.len() > self.chunk {
+            return Err(ReassemblyError::Mismatch);
+        }
+
+        let offset = f.index as usize * self.chunk;
+        let end = offset + f.data.len();
+        if end > N {
+            return Err(ReassemblyError::Overflow);
+        }
+
+        self.total = Some(f.total);
+        self.buff[offset..end].copy_from_slice(f.data);
+        self.received[f.index as usize / 32] |= 1 << (f.index % 32);
+        if last {
+            self.len = end;
+        }
+
+        if self.missing().next().is_some() {
+            return Ok(None);
+        }
+
+        // Payload complete, reset for the next payload
+        let len = self.len;
+        self.reset();
+
+        Ok(Some(&self.buff[..len]))
+    }
+
+    /// Fetch the indices of fragments not yet received
+    pub fn missing(&self) -> impl Iterator<Item = u8> + '_ {
+        let total = self.total.unwrap_or(0);
+
+        (0..total).filter(move |i| !self.has(*i))
+    }
+
+    fn has(&self, index: u8) -> bool {
+        self.received[index as usize / 32] & (1 << (index % 32)) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragment_reassemble() {
+        let mut data = [0u8; 100];
+        for (i, d) in data.iter_mut().enumerate() {
+            *d = i as u8;
+        }
+
+        let frags = Fragmenter::new();
+        let mut r = Reassembler::<128>::new(32);
+        let mut buff = [0u8; 32];
+
+        // Deliver fragments out of order
+        let mut encoded = [[0u8; 32]; 4];
+        let mut lens = [0usize; 4];
+        for f in frags.fragment(&data, 32) {
+            assert_eq!(f.total, 4);
+            lens[f.index as usize] = f.encode(&mut encoded[f.index as usize]);
+        }
+
+        for i in [3, 0, 2] {
+            assert_eq!(r.push(&encoded[i][..lens[i]]), Ok(None));
+        }
+        assert!(r.missing().eq([1]));
+
+        assert_eq!(r.push(&encoded[1][..lens[1]]), Ok(Some(&data[..])));
+
+        // Malformed and mismatched fragments
+        assert_eq!(r.push(&[0x00]), Err(ReassemblyError::Malformed));
+        let n = Fragment {
+            index: 0,
+            total: 2,
+            data: &[0xaa; 10],
+        }
+        .encode(&mut buff);
+        assert_eq!(r.push(&buff[..n]), Err(ReassemblyError::Mismatch));
+    }
+
+    #[test]
+    fn fragment_back_to_back() {
+        let a = [0xaa; 40];
+        let b = [0xbb; 40];
+        let mut r = Reassembler::<64>::new(32);
+        let mut buff = [0u8; 32];
+
+        // Consecutive payloads with the same fragment count, completing only on the last fragment
+        for data in [&a, &b] {
+            let mut frags = Fragmenter::new().fragment(data, 32).peekable();
+            while let Some(f) = frags.next() {
+                let n = f.encode(&mut buff);
+                let res = r.push(&buff[..n]).unwrap().map(|d| d.to_vec());
+                match frags.peek() {
+                    Some(_) => assert_eq!(res, None),
+                    None => assert_eq!(res.as_deref(), Some(&data[..])),
+                }
+            }
+        }
+
+        // A partial payload is discarded when a new payload starts
+        let first = Fragmenter::new().fragment(&a, 32).next().unwrap();
+        let n = first.encode(&mut buff);
+        assert_eq!(r.push(&buff[..n]), Ok(None));
+
+        let mut res = None;
+        for f in Fragmenter::new().fragment(&b, 32) {
+            let n = f.encode(&mut buff);
+            res = r.push(&buff[..n]).unwrap().map(|d| d.to_vec());
+        }
+        assert_eq!(res.as_deref(), Some(&b[..]));
+    }
+
+    #[test]
+    fn fragment_overflow() {
+        let data = [0xaa; 64];
+        let mut r = Reassembler::<32>::new(18);
+
+        let mut buff = [0u8; 18];
+        let mut res = Ok(None);
+        for f in Fragmenter::new().fragment(&data, 18) {
+            let n = f.encode(&mut buff);
+            res = r.push(&buff[..n]).map(|_| None::<()>);
+            if res.is_err() {
+                break;
+            }
+        }
+
+        assert_eq!(res, Err(ReassemblyError::Overflow));
+    }
+}
@@ -3,19 +3,20 @@
 //! ## <https://github.com/rust-iot/radio-hal>
 //! ## Copyright 2020-2022 Ryan Kurte
 
+use std::collections::VecDeque;
 use std::ffi::CString;
 use std::fs::{File, OpenOptions};
 use std::prelude::v1::*;
 use std::string::String;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use libc::{self};
 
 #[cfg(not(feature = "defmt"))]
-use log::{debug, info};
+use log::{debug, info, warn};
 
 #[cfg(feature = "defmt")]
-use defmt::{debug, info};
+use defmt::{debug, info, warn};
 
 use clap::Parser;
 use embedded_hal::delay::DelayNs;
@@ -23,20 +24,31 @@ use humantime::Duration as HumanDuration;
 
 use byteorder::{ByteOrder, NetworkEndian};
 use pcap_file::{pcap::PcapHeader, DataLink, PcapWriter};
-use rolling_stats::Stats;
 
 use crate::*;
 use crate::{
-    blocking::{BlockingError, BlockingOptions, BlockingReceive, BlockingTransmit},
+    blocking::{
+        delay_us_long, BlockingError, BlockingOptions, BlockingReceive, BlockingSetState,
+        BlockingTransmit, MIN_POLL_INTERVAL,
+    },
+    config::{ConfigError, ConfigOption, ConfigSnapshot, Configure},
     Power, Receive, ReceiveInfo, Rssi, Transmit,
 };
 
+pub mod rssi;
+
+pub use crate::fcs::{ieee802154_fcs, ieee802154_fcs_verify, CrcKind, Fcs};
+pub use crate::fragment::{Fragment, Fragmenter, Reassembler, ReassemblyError};
+pub use crate::linktest::{read_info, InfoFormat, LinkTestInfo, PingPongOptions, RemoteInfo};
+pub use crate::rng::{Rng, XorShift32};
+pub use crate::stats::RunningStats;
+
 /// Basic operations supported by the helpers package
 #[derive(Clone, Parser, PartialEq, Debug)]
 pub enum Operation {
     #[clap(name = "tx")]
     /// Transmit a packet
-    Transmit(TransmitOptions),
+    Transmit(ConfiguredTransmitOptions),
 
     #[clap(name = "rx")]
     /// Receive a packet
@@ -70,7 +82,12 @@ where
 
     // TODO: the rest
     match operation {
-        Operation::Transmit(options) => do_transmit(radio, options)?,
+        Operation::Transmit(options) => {
+            if options.preamble_length.is_some() || options.no_crc {
+                warn!("Transmit configuration options require do_operation_configured, ignoring");
+            }
+            do_transmit(radio, options.transmit)?
+        }
         Operation::Receive(options) => do_receive(radio, &mut buff, options).map(|_| ())?,
         Operation::Echo(options) => do_echo(radio, &mut buff, options).map(|_| ())?,
         Operation::Rssi(options) => do_rssi(radio, options).map(|_| ())?,
@@ -81,6 +98,28 @@ where
     Ok(())
 }
 
+/// Execute an operation as with `do_operation`, applying transmit configuration options
+/// (see `do_transmit_configured`) for radios implementing `Configure`
+pub fn do_operation_configured<T, I, E>(
+    radio: &mut T,
+    operation: Operation,
+) -> Result<(), ConfiguredTransmitError<E>>
+where
+    T: Transmit<Error = E>
+        + Power<Error = E>
+        + Receive<Info = I, Error = E>
+        + Rssi<Error = E>
+        + Configure<Error = E>
+        + DelayNs,
+    I: ReceiveInfo + Default + std::fmt::Debug,
+    E: std::fmt::Debug,
+{
+    match operation {
+        Operation::Transmit(options) => do_transmit_configured(radio, options),
+        _ => do_operation(radio, operation).map_err(ConfiguredTransmitError::Radio),
+    }
+}
+
 /// Configuration for Transmit operation
 #[derive(Clone, Parser, PartialEq, Debug)]
 pub struct TransmitOptions {
@@ -124,6 +163,85 @@ where
     Ok(())
 }
 
+/// Configuration for Transmit operation with radio configuration applied prior to transmission
+#[derive(Clone, Parser, PartialEq, Debug)]
+pub struct ConfiguredTransmitOptions {
+    #[clap(flatten)]
+    pub transmit: TransmitOptions,
+
+    /// Preamble length (symbols for LoRa, bytes for FSK)
+    #[clap(long)]
+    pub preamble_length: Option<u16>,
+
+    /// Disable hardware CRC for the duration of the transmission, for raw frames
+    /// carrying their own integrity check (the previous setting is restored afterwards)
+    #[clap(long)]
+    pub no_crc: bool,
+}
+
+/// Errors from configured transmission
+#[derive(Debug)]
+pub enum ConfiguredTransmitError<E> {
+    /// Error applying radio configuration
+    Config(ConfigError<E>),
+    /// Error transmitting
+    Radio(BlockingError<E>),
+}
+
+/// Transmit as with `do_transmit`, first applying the provided radio configuration
+///
+/// Configuration is applied with `Configure::set_option`. The preamble length persists after
+/// transmission, while the CRC setting is read with `Configure::get_option` before being
+/// disabled and restored once transmission completes (or fails). Where the radio does not
+/// support reading the CRC setting, CRC is re-enabled following transmission.
+pub fn do_transmit_configured<T, E>(
+    radio: &mut T,
+    options: ConfiguredTransmitOptions,
+) -> Result<(), ConfiguredTransmitError<E>>
+where
+    T: Transmit<Error = E> + Power<Error = E> + Configure<Error = E> + DelayNs,
+    E: core::fmt::Debug,
+{
+    // Set preamble length if specified
+    if let Some(l) = options.preamble_length {
+        radio
+            .set_option(&ConfigOption::PreambleLength(l))
+            .map_err(ConfiguredTransmitError::Config)?;
+    }
+
+    // Disable CRC if specified, saving the current setting for restore
+    let crc = match options.no_crc {
+        true => {
+            // Radios unable to read back the CRC setting have CRC re-enabled afterwards
+            let mut prev = ConfigOption::Crc(true);
+            match radio.get_option(&mut prev) {
+                Ok(()) => (),
+                Err(ConfigError::NotSupported) => {
+                    warn!("Unable to read CRC setting, CRC will be re-enabled after transmit")
+                }
+                Err(e) => return Err(ConfiguredTransmitError::Config(e)),
+            }
+            radio
+                .set_option(&ConfigOption::Crc(false))
+                .map_err(ConfiguredTransmitError::Config)?;
+            Some(prev)
+        }
+        false => None,
+    };
+
+    let res = do_transmit(radio, options.transmit).map_err(ConfiguredTransmitError::Radio);
+
+    // Restore previous CRC setting, reporting transmit errors in preference to restore errors
+    let restore = match crc {
+        Some(prev) => radio
+            .set_option(&prev)
+            .map_err(ConfiguredTransmitError::Config),
+        None => Ok(()),
+    };
+
+    res.and(restore)
+}
+
 /// Configuration for Receive operation
 #[derive(Clone, Parser, PartialEq, Debug)]
 pub struct ReceiveOptions {
@@ -131,6 +249,18 @@ pub struct ReceiveOptions {
     #[clap(long = "continuous")]
     pub continuous: bool,
 
+    /// Show frames failing CRC checks (these are not written to PCAP outputs)
+    #[clap(long)]
+    pub show_invalid: bool,
+
+    /// Delay after entering receive mode to allow AGC settling
+    #[clap(long, default_value = "0s")]
+    pub agc_settle: HumanDuration,
+
+    /// Check the IEEE 802.15.4 FCS of received frames, logging frames with a bad FCS
+    #[clap(long)]
+    pub check_fcs: bool,
+
     #[clap(flatten)]
     pub pcap_options: PcapOptions,
 
@@ -148,13 +278,38 @@ pub struct PcapOptions {
     /// Create and write to a unix pipe for connection to wireshark
     #[clap(long, group = "1")]
     pub pcap_pipe: Option<String>,
+
+    /// Rotate PCAP files once they reach the specified size in bytes
+    #[clap(long)]
+    pub pcap_rotate_size: Option<u64>,
+
+    /// Rotate PCAP files once they have been open for the specified duration
+    #[clap(long)]
+    pub pcap_rotate_time: Option<HumanDuration>,
 }
 
 impl PcapOptions {
+    /// Check whether PCAP file rotation is enabled
+    ///
+    /// Rotation only applies to file outputs, pipes are never rotated
+    pub fn rotate(&self) -> bool {
+        self.pcap_file.is_some()
+            && (self.pcap_rotate_size.is_some() || self.pcap_rotate_time.is_some())
+    }
+
     pub fn open(&self) -> Result<Option<PcapWriter<File>>, std::io::Error> {
+        self.open_index(0)
+    }
+
+    /// Open the PCAP output, using the numbered file for `index` when rotation is enabled
+    fn open_index(&self, index: usize) -> Result<Option<PcapWriter<File>>, std::io::Error> {
         // Open file or pipe if specified
         let pcap_file = match (&self.pcap_file, &self.pcap_pipe) {
             // Open as file
+            (Some(file), None) if self.rotate() => {
+                let f = File::create(rotate_path(file, index))?;
+                Some(f)
+            }
             (Some(file), None) => {
                 let f = File::create(file)?;
                 Some(f)
@@ -175,6 +330,8 @@ impl PcapOptions {
                     panic!("Error creating fifo: {}", status);
                 }
 
+                info!("pcap pipe open, awaiting connection");
+
                 // Open pipe
                 let f = OpenOptions::new()
                     .write(true)
@@ -189,9 +346,6 @@ impl PcapOptions {
             _ => unimplemented!(),
         };
 
-        #[cfg(any(feature = "log", feature = "defmt"))]
-        info!("pcap pipe open, awaiting connection");
-
         // Setup pcap writer and write header
         // (This is a blocking operation on pipes)
         let pcap_writer = match pcap_file {
@@ -211,6 +365,92 @@ impl PcapOptions {
     }
 }
 
+/// Build the numbered path for a rotated PCAP file (`capture.pcap` -> `capture-N.pcap`)
+fn rotate_path(file: &str, index: usize) -> String {
+    let p = std::path::Path::new(file);
+    let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or(file);
+
+    let name = match p.extension().and_then(|e| e.to_str()) {
+        Some(e) => format!("{}-{}.{}", stem, index, e),
+        None => format!("{}-{}", stem, index),
+    };
+
+    p.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// PCAP capture output, rotating between numbered files when configured
+pub struct PcapCapture {
+    options: PcapOptions,
+    writer: Option<PcapWriter<File>>,
+    index: usize,
+    written: u64,
+    opened: Instant,
+}
+
+impl PcapCapture {
+    /// Open PCAP capture output using the provided options
+    pub fn new(options: PcapOptions) -> Result<Self, std::io::Error> {
+        let writer = options.open()?;
+
+        Ok(Self {
+            options,
+            writer,
+            index: 0,
+            written: 0,
+            opened: Instant::now(),
+        })
+    }
+
+    /// Write a packet to the capture, rotating files first if limits have been reached
+    pub fn write(&mut self, data: &[u8]) -> Result<(), pcap_file::PcapError> {
+        if self.writer.is_none() {
+            return Ok(());
+        }
+
+        // Check rotation limits
+        let size_exceeded = self
+            .options
+            .pcap_rotate_size
+            .map(|s| self.written >= s)
+            .unwrap_or(false);
+        let time_exceeded = self
+            .options
+            .pcap_rotate_time
+            .map(|t| self.opened.elapsed() >= *t)
+            .unwrap_or(false);
+
+        if self.options.rotate() && (size_exceeded || time_exceeded) {
+            // Drop the previous writer to close the file before opening the next
+            self.writer = None;
+            self.index += 1;
+
+            info!("Rotating pcap file (index: {})", self.index);
+
+            self.writer = self.options.open_index(self.index)?;
+            self.written = 0;
+            self.opened = Instant::now();
+        }
+
+        let t = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        if let Some(p) = &mut self.writer {
+            p.write(
+                t.as_secs() as u32,
+                t.as_nanos() as u32 % 1_000_000,
+                data,
+                data.len() as u32,
+            )?;
+
+            // Account for the 16 byte record header
+            self.written += data.len() as u64 + 16;
+        }
+
+        Ok(())
+    }
+}
+
 /// Receive from the radio using the provided configuration
 pub fn do_receive<T, I, E>(
     radio: &mut T,
@@ -223,17 +463,41 @@ where
     E: std::fmt::Debug,
 {
     // Create and open pcap file for writing
-    let mut pcap_writer = options
-        .pcap_options
-        .open()
-        .expect("Error opening pcap file / pipe");
+    let mut pcap =
+        PcapCapture::new(options.pcap_options.clone()).expect("Error opening pcap file / pipe");
 
     // Start receive mode
     radio.start_receive()?;
 
+    // Wait for AGC to settle
+    if !options.agc_settle.is_zero() {
+        radio.delay_us(options.agc_settle.as_micros() as u32);
+    }
+
     loop {
-        if radio.check_receive(true)? {
-            let (n, i) = radio.get_received(&mut buff)?;
+        // Receive without restarting on errors if invalid frames are to be shown
+        if radio.check_receive(!options.show_invalid)? {
+            let (n, i, valid) = match options.show_invalid {
+                true => radio.get_received_raw(buff)?,
+                false => {
+                    let (n, i) = radio.get_received(&mut buff)?;
+                    (n, i, true)
+                }
+            };
+
+            if !valid {
+                #[cfg(not(feature = "defmt"))]
+                info!("Received invalid: '{:02x?}' info: {:?}", &buff[0..n], i);
+                #[cfg(feature = "defmt")]
+                info!("Received invalid: '{:?}' info: {:?}", &buff[0..n], i);
+
+                radio.start_receive()?;
+                continue;
+            }
+
+            if options.check_fcs && !ieee802154_fcs_verify(&buff[0..n]) {
+                info!("Bad FCS");
+            }
 
             match std::str::from_utf8(&buff[0..n as usize]) {
                 Ok(s) => info!("Received: '{}' info: {:?}", s, i),
@@ -243,19 +507,7 @@ where
                 Err(_) => info!("Received: '{:?}' info: {:?}", &buff[0..n as usize], i),
             }
 
-            if let Some(p) = &mut pcap_writer {
-                let t = SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap();
-
-                p.write(
-                    t.as_secs() as u32,
-                    t.as_nanos() as u32 % 1_000_000,
-                    &buff[0..n],
-                    n as u32,
-                )
-                .expect("Error writing pcap file");
-            }
+            pcap.write(&buff[0..n]).expect("Error writing pcap file");
 
             if !options.continuous {
                 return Ok(n);
@@ -264,7 +516,7 @@ where
             radio.start_receive()?;
         }
 
-        radio.delay_us(options.blocking_options.poll_interval.as_micros() as u32);
+        radio.delay_us(options.blocking_options.clamped_poll_interval().as_micros() as u32);
     }
 }
 
@@ -278,6 +530,10 @@ pub struct RssiOptions {
     /// Run continuously
     #[clap(long = "continuous")]
     pub continuous: bool,
+
+    /// Delay after entering receive mode to allow AGC settling before sampling
+    #[clap(long, default_value = "0s")]
+    pub agc_settle: HumanDuration,
 }
 
 pub fn do_rssi<T, I, E>(radio: &mut T, options: RssiOptions) -> Result<(), E>
@@ -289,13 +545,18 @@ where
     // Enter receive mode
     radio.start_receive()?;
 
+    // Wait for AGC to settle
+    if !options.agc_settle.is_zero() {
+        radio.delay_us(options.agc_settle.as_micros() as u32);
+    }
+
     // Poll for RSSI
     loop {
         let rssi = radio.poll_rssi()?;
 
         info!("rssi: {}", rssi);
 
-        radio.check_receive(true)?;
+        radio.check_receive_continuous()?;
 
         radio.delay_us(options.period.as_micros() as u32);
 
@@ -326,6 +587,10 @@ pub struct EchoOptions {
     #[clap(long = "append-info")]
     pub append_info: bool,
 
+    /// Format for appended receive info
+    #[clap(long, value_enum, default_value = "rssi")]
+    pub info_format: InfoFormat,
+
     #[clap(flatten)]
     pub blocking_options: BlockingOptions,
 }
@@ -349,7 +614,7 @@ where
     radio.start_receive()?;
 
     loop {
-        if radio.check_receive(true)? {
+        if radio.check_receive_continuous()? {
             // Fetch received packet
             let (mut n, i) = radio.get_received(&mut buff)?;
 
@@ -362,10 +627,14 @@ where
                 Err(_) => info!("Received: '{:?}' info: {:?}", &buff[0..n as usize], i),
             }
 
-            // Append info if provided
+            // Append info if provided and space is available
             if options.append_info {
-                NetworkEndian::write_i16(&mut buff[n..], i.rssi());
-                n += 2;
+                let info = RemoteInfo::from_info(&i);
+                if info.encoded_len(options.info_format) <= buff.len() - n {
+                    n += info.encode(options.info_format, &mut buff[n..]);
+                } else {
+                    warn!("Insufficient space to append info, echoing frame only");
+                }
             }
 
             // Wait for turnaround delay
@@ -381,112 +650,2736 @@ where
         }
 
         // Wait for poll delay
-        radio.delay_us(options.blocking_options.poll_interval.as_micros() as u32);
+        radio.delay_us(options.blocking_options.clamped_poll_interval().as_micros() as u32);
     }
 }
 
-/// Configuration for Echo operation
-#[derive(Clone, Parser, PartialEq, Debug)]
-pub struct PingPongOptions {
-    /// Specify the number of rounds to tx/rx
-    #[clap(long, default_value = "100")]
-    pub rounds: u32,
+/// Transmit a packet then immediately enter receive mode, for request / response turnaround
+///
+/// Transmit completion and receive state are polled at `MIN_POLL_INTERVAL` (rather than
+/// `options.poll_interval`) to minimise the turnaround, with `options.timeout` bounding
+/// each. Returns the measured turnaround time from transmit completion until the radio
+/// reports a receive state (see `RadioState::is_rx`).
+pub fn tx_then_rx<T, E>(
+    radio: &mut T,
+    data: &[u8],
+    options: BlockingOptions,
+) -> Result<std::time::Duration, BlockingError<E>>
+where
+    T: Transmit<Error = E> + Receive<Error = E> + State<Error = E> + DelayNs,
+    E: std::fmt::Debug,
+{
+    // Check payload fits within the radio MTU
+    if let Some(mtu) = T::MTU {
+        if data.len() > mtu {
+            return Err(BlockingError::PayloadTooLarge);
+        }
+    }
 
-    /// Power in dBm (range -18dBm to 13dBm)
-    #[clap(long)]
-    pub power: Option<i8>,
+    let interval = MIN_POLL_INTERVAL.as_micros() as u32;
+    let timeout = options.timeout.as_micros();
 
-    /// Specify delay for response message
-    #[clap(long, default_value = "100ms")]
-    pub delay: HumanDuration,
+    // Transmit and await completion
+    radio.start_transmit(data)?;
 
-    /// Parse RSSI and other info from response messages
-    /// (echo server must have --append-info set)
-    #[clap(long)]
-    pub parse_info: bool,
+    let mut c = 0;
+    while !radio.check_transmit()? {
+        c += interval as u128;
+        if c > timeout {
+            debug!("TX to RX transmit timeout");
+            return Err(BlockingError::Timeout);
+        }
+        radio.delay_us(interval);
+    }
 
-    #[clap(flatten)]
-    pub blocking_options: BlockingOptions,
+    // Switch to receive as soon as possible, awaiting the receive state
+    let t = Instant::now();
+    radio.start_receive()?;
+
+    let mut c = 0;
+    while !radio.get_state()?.is_rx() {
+        c += interval as u128;
+        if c > timeout {
+            debug!("TX to RX receive state timeout");
+            return Err(BlockingError::Timeout);
+        }
+        radio.delay_us(interval);
+    }
+
+    let turnaround = t.elapsed();
+
+    debug!("TX to RX turnaround: {:?}", turnaround);
+
+    Ok(turnaround)
 }
 
-pub struct LinkTestInfo {
-    pub sent: u32,
-    pub received: u32,
-    pub local_rssi: Stats<f32>,
-    pub remote_rssi: Stats<f32>,
+/// Receive on any of the provided channels, listening on each for `dwell` in turn
+///
+/// Channels are scanned round-robin until a packet is received, returning the packet length,
+/// info, and the index of the channel it was received on. `options.timeout` bounds the overall
+/// scan, while `options.poll_interval` is used for polling within each dwell.
+///
+/// Returns `BlockingError::InvalidOptions` where `dwell` is zero.
+pub fn scan_receive<T, I, E>(
+    radio: &mut T,
+    channels: &[<T as Channel>::Channel],
+    dwell: std::time::Duration,
+    buff: &mut [u8],
+    options: BlockingOptions,
+) -> Result<(usize, I, usize), BlockingError<E>>
+where
+    T: Channel<Error = E> + Receive<Info = I, Error = E> + DelayNs,
+    I: std::fmt::Debug,
+    E: std::fmt::Debug,
+{
+    // Elapsed time is counted in dwells, so a zero dwell would never time out
+    if dwell.is_zero() {
+        return Err(BlockingError::InvalidOptions);
+    }
+
+    let dwell_options = BlockingOptions {
+        poll_interval: options.poll_interval,
+        timeout: dwell,
+    };
+
+    let mut elapsed = std::time::Duration::ZERO;
+    for (i, ch) in channels.iter().enumerate().cycle() {
+        if elapsed >= options.timeout {
+            break;
+        }
+
+        radio.set_channel(ch)?;
+
+        match radio.do_receive(buff, dwell_options.clone()) {
+            Ok((n, info)) => {
+                debug!("Scan received on channel {:?}", ch);
+                return Ok((n, info, i));
+            }
+            Err(BlockingError::Timeout) => (),
+            Err(e) => return Err(e),
+        }
+
+        elapsed += dwell;
+    }
+
+    debug!("Scan receive timeout");
+    Err(BlockingError::Timeout)
 }
 
-pub fn do_ping_pong<T, I, E>(
+/// Errors from `receive_auto`
+#[derive(Debug)]
+pub enum AutoReceiveError<E, P> {
+    /// Underlying blocking receive error
+    Radio(BlockingError<E>),
+    /// IRQ pin error
+    Pin(P),
+}
+
+impl<E, P> From<BlockingError<E>> for AutoReceiveError<E, P> {
+    fn from(e: BlockingError<E>) -> Self {
+        AutoReceiveError::Radio(e)
+    }
+}
+
+/// Receive a packet, using the radio IRQ pin where provided and falling back to polling
+///
+/// - With `Some(irq)` receive is started then the (active high) IRQ pin is sampled at
+///   `options.poll_interval`, with the radio only being queried via `check_receive` once
+///   the IRQ is asserted. This avoids bus traffic while waiting for packets.
+/// - With `None` this is equivalent to `BlockingReceive::do_receive`, polling the radio
+///   with `check_receive` at `options.poll_interval`.
+///
+/// As the pin type can not be inferred from `None`, callers without a pin should specify
+/// one, e.g. `receive_auto(radio, None::<&mut MyPin>, ..)`.
+pub fn receive_auto<T, P, I, E>(
     radio: &mut T,
-    options: PingPongOptions,
-) -> Result<LinkTestInfo, BlockingError<E>>
+    irq: Option<&mut P>,
+    buff: &mut [u8],
+    options: BlockingOptions,
+) -> Result<(usize, I), AutoReceiveError<E, P::Error>>
 where
-    T: Receive<Info = I, Error = E> + Transmit<Error = E> + Power<Error = E> + DelayNs,
-    I: ReceiveInfo,
+    T: Receive<Info = I, Error = E> + DelayNs,
+    P: embedded_hal::digital::InputPin,
+    I: std::fmt::Debug,
     E: std::fmt::Debug,
 {
-    let mut link_info = LinkTestInfo {
-        sent: options.rounds,
-        received: 0,
-        local_rssi: Stats::new(),
-        remote_rssi: Stats::new(),
+    let irq = match irq {
+        Some(p) => p,
+        None => {
+            debug!("Receive auto: no IRQ pin, polling radio");
+            return Ok(radio.do_receive(buff, options)?);
+        }
     };
 
-    let mut buff = [0u8; 32];
+    debug!("Receive auto: using IRQ pin");
 
-    // Set output power if specified
-    if let Some(p) = options.power {
-        radio.set_power(p)?;
+    radio.start_receive().map_err(BlockingError::Inner)?;
+
+    let t = options.timeout.as_micros();
+    let mut c = 0;
+    loop {
+        if irq.is_high().map_err(AutoReceiveError::Pin)?
+            && radio
+                .check_receive_continuous()
+                .map_err(BlockingError::Inner)?
+        {
+            let (n, i) = radio.get_received(buff).map_err(BlockingError::Inner)?;
+            return Ok((n, i));
+        }
+
+        c += options.clamped_poll_interval().as_micros();
+        if c > t {
+            debug!("Receive auto timeout");
+            return Err(BlockingError::Timeout.into());
+        }
+
+        radio.delay_us(options.clamped_poll_interval().as_micros() as u32);
     }
+}
 
-    for i in 0..options.rounds {
-        // Encode message
-        NetworkEndian::write_u32(&mut buff[0..], i as u32);
-        let n = 4;
+/// Run channel activity detection, entering receive only when activity is detected
+///
+/// This is the standard low-power LoRa listening pattern, returning `None` quickly
+/// when the channel is clear. `options` bounds both CAD and the subsequent receive.
+pub fn cad_receive<T, I, E>(
+    radio: &mut T,
+    buff: &mut [u8],
+    options: BlockingOptions,
+) -> Result<Option<(usize, I)>, BlockingError<E>>
+where
+    T: Cad<Error = E> + Receive<Info = I, Error = E> + DelayNs,
+    I: std::fmt::Debug,
+    E: std::fmt::Debug,
+{
+    // Start CAD and await completion
+    radio.start_cad()?;
+
+    let t = options.timeout.as_micros();
+    let mut c = 0;
+    let detected = loop {
+        if let Some(d) = radio.check_cad()? {
+            break d;
+        }
 
-        debug!("Sending message {}", i);
+        c += options.clamped_poll_interval().as_micros();
+        if c > t {
+            debug!("CAD timeout");
+            return Err(BlockingError::Timeout);
+        }
 
-        // Send message
-        radio.do_transmit(&buff[0..n], options.blocking_options.clone())?;
+        radio.delay_us(options.clamped_poll_interval().as_micros() as u32);
+    };
 
-        // Await response
-        let (n, info) = match radio.do_receive(&mut buff, options.blocking_options.clone()) {
-            Ok(r) => r,
-            Err(BlockingError::Timeout) => {
-                debug!("Timeout awaiting response {}", i);
-                continue;
+    if !detected {
+        debug!("CAD channel clear");
+        return Ok(None);
+    }
+
+    // Activity detected, receive
+    debug!("CAD activity detected, entering receive");
+    radio.do_receive(buff, options).map(Some)
+}
+
+/// Set the radio channel and immediately transmit, for frequency-agile protocols
+///
+/// Returns the measured latency of the channel change
+pub fn transmit_on<T, E>(
+    radio: &mut T,
+    channel: &<T as Channel>::Channel,
+    data: &[u8],
+    options: BlockingOptions,
+) -> Result<std::time::Duration, BlockingError<E>>
+where
+    T: Channel<Error = E> + Transmit<Error = E> + DelayNs,
+    E: std::fmt::Debug,
+{
+    // Switch channel
+    let t = Instant::now();
+    radio.set_channel(channel)?;
+    let latency = t.elapsed();
+
+    // Transmit and await completion
+    radio.do_transmit(data, options)?;
+
+    debug!("Channel change latency: {:?}", latency);
+
+    Ok(latency)
+}
+
+/// Summary of a streamed transmission
+#[derive(Clone, PartialEq, Debug)]
+pub struct StreamSummary {
+    /// Total bytes transmitted
+    pub bytes: usize,
+    /// Total frames transmitted
+    pub frames: usize,
+}
+
+/// Errors from streamed transmission
+#[derive(Debug)]
+pub enum StreamError<E> {
+    /// Error reading from the input stream
+    Io(std::io::Error),
+    /// Error transmitting a frame
+    Radio(BlockingError<E>),
+}
+
+/// Transmit data read from a stream as frames of (up to) `frame_len` bytes
+///
+/// Frames are read and transmitted one at a time, with a short final frame sent
+/// where the stream length is not a multiple of `frame_len`.
+pub fn transmit_stream<T, R, E>(
+    radio: &mut T,
+    mut reader: R,
+    frame_len: usize,
+    options: BlockingOptions,
+) -> Result<StreamSummary, StreamError<E>>
+where
+    T: Transmit<Error = E> + DelayNs,
+    R: std::io::Read,
+    E: std::fmt::Debug,
+{
+    let mut buff = vec![0u8; frame_len];
+    let mut summary = StreamSummary {
+        bytes: 0,
+        frames: 0,
+    };
+
+    loop {
+        // Fill frame, stopping early at end of stream
+        let mut n = 0;
+        while n < frame_len {
+            match reader.read(&mut buff[n..]) {
+                Ok(0) => break,
+                Ok(r) => n += r,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(StreamError::Io(e)),
             }
-            Err(e) => return Err(e),
-        };
+        }
 
-        let receive_index = NetworkEndian::read_u32(&buff[0..n]);
-        if receive_index != i {
-            debug!("Invalid receive index");
-            continue;
+        if n == 0 {
+            break;
         }
 
-        // Parse info if provided
-        let remote_rssi = match options.parse_info {
-            true => Some(NetworkEndian::read_i16(&buff[4..n])),
-            false => None,
-        };
+        radio
+            .do_transmit(&buff[..n], options.clone())
+            .map_err(StreamError::Radio)?;
 
-        debug!(
-            "Received response {} with local rssi: {} and remote rssi: {:?}",
-            receive_index,
-            info.rssi(),
-            remote_rssi
-        );
+        summary.bytes += n;
+        summary.frames += 1;
+
+        debug!("Sent stream frame {} ({} bytes)", summary.frames, n);
 
-        link_info.received += 1;
-        link_info.local_rssi.update(info.rssi() as f32);
-        if let Some(rssi) = remote_rssi {
-            link_info.remote_rssi.update(rssi as f32);
+        if n < frame_len {
+            break;
         }
+    }
 
-        // Wait for send delay
-        radio.delay_us(options.delay.as_micros() as u32);
+    Ok(summary)
+}
+
+/// Options for replaying captured frames
+#[derive(Clone, Parser, PartialEq, Debug)]
+pub struct ReplayOptions {
+    /// Fixed gap between transmitted frames, overriding capture timestamps
+    #[clap(long)]
+    pub gap: Option<HumanDuration>,
+
+    /// Blocking options for each transmission
+    #[clap(flatten)]
+    pub blocking_options: BlockingOptions,
+}
+
+/// Errors from pcap replay
+#[derive(Debug)]
+pub enum ReplayError<E> {
+    /// Error opening or reading the capture
+    Pcap(pcap_file::PcapError),
+    /// Error transmitting a frame
+    Radio(BlockingError<E>),
+}
+
+/// Replay frames from a pcap file at `path`, returning the number of frames sent
+///
+/// See [`replay_pcap_reader`] for timing behaviour.
+pub fn replay_pcap<T, P, E>(
+    radio: &mut T,
+    path: P,
+    options: ReplayOptions,
+) -> Result<usize, ReplayError<E>>
+where
+    T: Transmit<Error = E> + DelayNs,
+    P: AsRef<std::path::Path>,
+    E: std::fmt::Debug,
+{
+    let file = File::open(path).map_err(|e| ReplayError::Pcap(e.into()))?;
+    replay_pcap_reader(radio, file, options)
+}
+
+/// Replay frames from a pcap stream, returning the number of frames sent
+///
+/// Frames are spaced using the difference between record timestamps unless
+/// a fixed `gap` is provided in the options.
+pub fn replay_pcap_reader<T, R, E>(
+    radio: &mut T,
+    reader: R,
+    options: ReplayOptions,
+) -> Result<usize, ReplayError<E>>
+where
+    T: Transmit<Error = E> + DelayNs,
+    R: std::io::Read,
+    E: std::fmt::Debug,
+{
+    let pcap = pcap_file::PcapReader::new(reader).map_err(ReplayError::Pcap)?;
+
+    let mut last: Option<std::time::Duration> = None;
+    let mut frames = 0;
+
+    for p in pcap {
+        let p = p.map_err(ReplayError::Pcap)?;
+        let ts = p.header.timestamp();
+
+        // Wait between frames, the first is sent immediately
+        if let Some(last) = last {
+            let gap = match &options.gap {
+                Some(g) => **g,
+                None => ts.saturating_sub(last),
+            };
+            delay_us_long(radio, u64::try_from(gap.as_micros()).unwrap_or(u64::MAX));
+        }
+        last = Some(ts);
+
+        radio
+            .do_transmit(&p.data, options.blocking_options.clone())
+            .map_err(ReplayError::Radio)?;
+
+        frames += 1;
+
+        debug!("Replayed frame {} ({} bytes)", frames, p.data.len());
     }
 
-    Ok(link_info)
+    Ok(frames)
+}
+
+/// Measured radio state transition latencies
+#[derive(Clone, PartialEq, Debug)]
+pub struct TransitionLatencies {
+    /// Time to transition from idle to receive
+    pub idle_to_rx: std::time::Duration,
+    /// Time to transition from idle to transmit
+    pub idle_to_tx: std::time::Duration,
+    /// Time to transition from sleep to idle
+    pub sleep_to_idle: std::time::Duration,
+}
+
+/// Measure radio state transition latencies using `set_state_checked`
+///
+/// As receive and transmit states are radio specific these must be provided by the caller.
+/// The radio is left in the idle state on completion.
+pub fn measure_transitions<T, S, E>(
+    radio: &mut T,
+    rx_state: S,
+    tx_state: S,
+    options: BlockingOptions,
+) -> Result<TransitionLatencies, BlockingError<E>>
+where
+    T: State<State = S, Error = E> + DelayNs,
+    S: RadioState + PartialEq + Copy,
+    E: std::fmt::Debug,
+{
+    // Time a transition from the current state
+    let measure = |radio: &mut T, state: S| -> Result<_, BlockingError<E>> {
+        let t = Instant::now();
+        radio.set_state_checked(state, options.clone())?;
+        Ok(t.elapsed())
+    };
+
+    measure(radio, S::idle())?;
+    let idle_to_rx = measure(radio, rx_state)?;
+
+    measure(radio, S::idle())?;
+    let idle_to_tx = measure(radio, tx_state)?;
+
+    measure(radio, S::idle())?;
+    measure(radio, S::sleep())?;
+    let sleep_to_idle = measure(radio, S::idle())?;
+
+    let latencies = TransitionLatencies {
+        idle_to_rx,
+        idle_to_tx,
+        sleep_to_idle,
+    };
+
+    debug!("State transition latencies: {:?}", latencies);
+
+    Ok(latencies)
+}
+
+pub fn do_ping_pong<T, I, E>(
+    radio: &mut T,
+    options: PingPongOptions,
+) -> Result<LinkTestInfo, BlockingError<E>>
+where
+    T: Receive<Info = I, Error = E> + Transmit<Error = E> + Power<Error = E> + DelayNs,
+    I: ReceiveInfo,
+    E: std::fmt::Debug,
+{
+    let mut link_info = LinkTestInfo::new(options.rounds);
+
+    let mut buff = [0u8; 32];
+
+    // Set output power if specified
+    if let Some(p) = options.power {
+        radio.set_power(p)?;
+    }
+
+    for i in 0..options.rounds {
+        // Encode message
+        let n = LinkTestInfo::encode_ping(&mut buff, i);
+
+        debug!("Sending message {}", i);
+
+        // Send message
+        radio.do_transmit(&buff[0..n], options.blocking_options.clone())?;
+
+        // Await response
+        let (n, info) = match radio.do_receive(&mut buff, options.blocking_options.clone()) {
+            Ok(r) => r,
+            Err(BlockingError::Timeout) => {
+                debug!("Timeout awaiting response {}", i);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Update link statistics
+        let parse_info = options.parse_info.then_some(options.info_format);
+        if !link_info.update(i, &buff[0..n], &info, parse_info) {
+            continue;
+        }
+
+        // Wait for send delay
+        radio.delay_us(options.delay.as_micros() as u32);
+    }
+
+    Ok(link_info)
+}
+
+/// Relative clock drift estimate between local and remote radios
+#[derive(Clone, PartialEq, Debug)]
+pub struct DriftEstimate {
+    /// Number of timestamp pairs used in the estimate
+    pub samples: u32,
+    /// Remote clock offset from the local clock at the last sample, in microseconds
+    pub offset_us: i64,
+    /// Remote clock drift rate relative to the local clock, in parts per million
+    pub drift_ppm: f64,
+}
+
+/// Estimate relative clock drift against an echo peer using timestamped round-trips
+///
+/// This requires the local radio to report receive timestamps (`ReceiveInfo::timestamp`)
+/// and the echo server to append extended info (`--append-info --info-format extended`)
+/// including its own receive timestamp. Drift is estimated with a least squares fit of
+/// remote against local timestamps, returning `None` where fewer than two rounds
+/// provided timestamps.
+pub fn estimate_clock_drift<T, I, E>(
+    radio: &mut T,
+    rounds: u32,
+    options: BlockingOptions,
+) -> Result<Option<DriftEstimate>, BlockingError<E>>
+where
+    T: Receive<Info = I, Error = E> + Transmit<Error = E> + DelayNs,
+    I: ReceiveInfo,
+    E: std::fmt::Debug,
+{
+    let mut buff = [0u8; 32];
+    let mut samples = Vec::with_capacity(rounds as usize);
+
+    for i in 0..rounds {
+        let n = LinkTestInfo::encode_ping(&mut buff, i);
+        radio.do_transmit(&buff[..n], options.clone())?;
+
+        let (n, info) = match radio.do_receive(&mut buff, options.clone()) {
+            Ok(r) => r,
+            Err(BlockingError::Timeout) => {
+                debug!("Timeout awaiting response {}", i);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let data = &buff[..n];
+        if data.get(0..4).map(NetworkEndian::read_u32) != Some(i) {
+            debug!("Invalid receive index");
+            continue;
+        }
+
+        let remote = read_info(data, InfoFormat::Extended).and_then(|r| r.timestamp);
+        match (info.timestamp(), remote) {
+            (Some(l), Some(r)) => samples.push((l, r)),
+            _ => debug!("Missing timestamp for response {}", i),
+        }
+    }
+
+    if samples.len() < 2 {
+        return Ok(None);
+    }
+
+    // Least squares fit of remote against local time, relative to the first sample
+    let (l0, r0) = samples[0];
+    let n = samples.len() as f64;
+    let points = samples
+        .iter()
+        .map(|(l, r)| (l.wrapping_sub(l0) as f64, r.wrapping_sub(r0) as f64));
+
+    let (sx, sy, sxx, sxy) = points.fold((0.0, 0.0, 0.0, 0.0), |(sx, sy, sxx, sxy), (x, y)| {
+        (sx + x, sy + y, sxx + x * x, sxy + x * y)
+    });
+
+    let denom = n * sxx - sx * sx;
+    if denom == 0.0 {
+        return Ok(None);
+    }
+    let slope = (n * sxy - sx * sy) / denom;
+
+    let (l, r) = samples[samples.len() - 1];
+
+    Ok(Some(DriftEstimate {
+        samples: samples.len() as u32,
+        offset_us: r as i64 - l as i64,
+        drift_ppm: (slope - 1.0) * 1e6,
+    }))
+}
+
+/// Configuration for transmit power sweep calibration
+#[derive(Clone, Parser, PartialEq, Debug)]
+pub struct PowerSweepOptions {
+    /// Number of ping rounds at each power level
+    #[clap(long, default_value = "10")]
+    pub rounds: u32,
+
+    /// Delay between ping rounds
+    #[clap(long, default_value = "100ms")]
+    pub delay: HumanDuration,
+
+    /// Format of receive info appended by the echo server
+    /// (echo server must have --append-info set)
+    #[clap(long, value_enum, default_value = "rssi")]
+    pub info_format: InfoFormat,
+
+    #[clap(flatten)]
+    pub blocking_options: BlockingOptions,
+}
+
+/// Power sweep result for a single transmit power level
+#[derive(Clone, PartialEq, Debug)]
+pub struct PowerSweepPoint {
+    /// Transmit power in dBm
+    pub power: i8,
+    /// Number of pings sent
+    pub sent: u32,
+    /// Number of responses received
+    pub received: u32,
+    /// RSSI reported by the echo peer
+    pub remote_rssi: RunningStats,
+}
+
+impl PowerSweepPoint {
+    /// CSV header for `PowerSweepPoint::to_csv` rows
+    pub const CSV_HEADER: &'static str = "power_dbm,sent,received,rssi_mean,rssi_min,rssi_max";
+
+    /// Format the point as a CSV row, with empty RSSI fields where no responses were received
+    pub fn to_csv(&self) -> String {
+        match self.remote_rssi.count {
+            0 => format!("{},{},{},,,", self.power, self.sent, self.received),
+            _ => format!(
+                "{},{},{},{:.1},{},{}",
+                self.power,
+                self.sent,
+                self.received,
+                self.remote_rssi.mean,
+                self.remote_rssi.min,
+                self.remote_rssi.max
+            ),
+        }
+    }
+}
+
+/// Step through transmit power levels, recording the RSSI reported back by an echo peer
+///
+/// This runs a ping-pong link test at each power level (see `do_ping_pong`), producing a
+/// power to RSSI table for PA calibration.
+pub fn power_sweep<T, I, E>(
+    radio: &mut T,
+    powers: &[i8],
+    options: PowerSweepOptions,
+) -> Result<Vec<PowerSweepPoint>, BlockingError<E>>
+where
+    T: Receive<Info = I, Error = E> + Transmit<Error = E> + Power<Error = E> + DelayNs,
+    I: ReceiveInfo,
+    E: std::fmt::Debug,
+{
+    let mut points = Vec::with_capacity(powers.len());
+
+    for p in powers {
+        let link = do_ping_pong(
+            radio,
+            PingPongOptions {
+                rounds: options.rounds,
+                power: Some(*p),
+                delay: *options.delay,
+                parse_info: true,
+                info_format: options.info_format,
+                blocking_options: options.blocking_options.clone(),
+            },
+        )?;
+
+        let point = PowerSweepPoint {
+            power: *p,
+            sent: link.sent,
+            received: link.received,
+            remote_rssi: link.remote_rssi,
+        };
+
+        debug!("Power sweep: {}", point.to_csv());
+
+        points.push(point);
+    }
+
+    Ok(points)
+}
+
+/// Radio wrapper that records configuration for replay after a reset
+///
+/// Successful `Configure::set_option` calls are recorded (replacing earlier values of
+/// the same option), and may be re-applied with `reapply` to restore configuration
+/// after the radio has been reset or has browned out. Resets are detected with
+/// `check_reset` for radios reporting `DeviceStatusFlags::RESET`, and configuration
+/// is re-applied automatically on `Shutdown::wakeup`. Other operations are delegated
+/// to the wrapped radio.
+pub struct PersistentConfigRadio<R> {
+    inner: R,
+    options: Vec<ConfigOption>,
+}
+
+impl<R> PersistentConfigRadio<R>
+where
+    R: Configure,
+{
+    /// Wrap a radio, recording subsequent configuration
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            options: Vec::new(),
+        }
+    }
+
+    /// Fetch recorded configuration options in the order first applied
+    pub fn options(&self) -> &[ConfigOption] {
+        &self.options
+    }
+
+    /// Re-apply all recorded configuration options to the radio
+    pub fn reapply(&mut self) -> Result<(), ConfigError<R::Error>> {
+        for o in &self.options {
+            debug!("Re-applying option {:?}", o);
+            self.inner.set_option(o)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a reference to the wrapped radio
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    ///
+    /// Note that configuration applied directly to the inner radio is not recorded
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Configure for PersistentConfigRadio<R>
+where
+    R: Configure,
+{
+    type Error = R::Error;
+
+    fn set_option(&mut self, o: &ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.set_option(o)?;
+
+        // Record option, replacing any previous value
+        match self
+            .options
+            .iter_mut()
+            .find(|v| core::mem::discriminant(*v) == core::mem::discriminant(o))
+        {
+            Some(v) => *v = o.clone(),
+            None => self.options.push(o.clone()),
+        }
+
+        Ok(())
+    }
+
+    fn get_option(&mut self, o: &mut ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.get_option(o)
+    }
+
+    fn effective_mtu(&mut self) -> Result<u16, ConfigError<Self::Error>> {
+        self.inner.effective_mtu()
+    }
+
+    fn frequency_range(&self) -> Option<(u32, u32)> {
+        self.inner.frequency_range()
+    }
+}
+
+impl<R, E> PersistentConfigRadio<R>
+where
+    R: Configure<Error = E> + DeviceStatus<Error = E>,
+    E: Debug,
+{
+    /// Check the radio status for a reset, re-applying recorded configuration and
+    /// clearing `DeviceStatusFlags::RESET` where a reset has occurred
+    ///
+    /// Returns true if a reset was detected. This is intended to be called periodically,
+    /// for example from a supervision loop.
+    pub fn check_reset(&mut self) -> Result<bool, ConfigError<E>> {
+        let status = self.inner.get_status().map_err(ConfigError::Other)?;
+        if !status.contains(DeviceStatusFlags::RESET) {
+            return Ok(false);
+        }
+
+        debug!("Radio reset detected, re-applying configuration");
+
+        self.reapply()?;
+        self.inner
+            .clear_status(DeviceStatusFlags::RESET)
+            .map_err(ConfigError::Other)?;
+
+        Ok(true)
+    }
+}
+
+impl<R, E> Shutdown for PersistentConfigRadio<R>
+where
+    R: Shutdown<Error = E> + Configure<Error = E>,
+    E: Debug,
+{
+    type Error = ConfigError<E>;
+
+    fn shutdown(&mut self) -> Result<(), Self::Error> {
+        self.inner.shutdown().map_err(ConfigError::Other)
+    }
+
+    /// Wake the radio, re-applying recorded configuration (lost on shutdown)
+    fn wakeup(&mut self) -> Result<(), Self::Error> {
+        self.inner.wakeup().map_err(ConfigError::Other)?;
+        self.reapply()
+    }
+}
+
+impl<R> Transmit for PersistentConfigRadio<R>
+where
+    R: Transmit,
+{
+    type Error = R::Error;
+
+    const MTU: Option<usize> = R::MTU;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.inner.start_transmit(data)
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        self.inner.check_transmit()
+    }
+
+    fn transmit_remaining(&mut self) -> Result<Option<Duration>, Self::Error> {
+        self.inner.transmit_remaining()
+    }
+
+    fn transmit_preamble(&mut self, duration: Duration) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.transmit_preamble(duration)
+    }
+}
+
+impl<R> Receive for PersistentConfigRadio<R>
+where
+    R: Receive,
+{
+    type Error = R::Error;
+    type Info = R::Info;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        self.inner.start_receive()
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        self.inner.check_receive(restart)
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        self.inner.get_received(buff)
+    }
+
+    fn get_received_raw(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<(usize, Self::Info, bool), Self::Error> {
+        self.inner.get_received_raw(buff)
+    }
+
+    fn rx_overflow(&mut self) -> Result<bool, Self::Error> {
+        self.inner.rx_overflow()
+    }
+
+    fn last_rx_was_broadcast(&mut self) -> Result<Option<bool>, Self::Error> {
+        self.inner.last_rx_was_broadcast()
+    }
+}
+
+impl<R> State for PersistentConfigRadio<R>
+where
+    R: State,
+{
+    type State = R::State;
+    type Error = R::Error;
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.inner.set_state(state)
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        self.inner.get_state()
+    }
+}
+
+impl<R> Rssi for PersistentConfigRadio<R>
+where
+    R: Rssi,
+{
+    type Error = R::Error;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        self.inner.poll_rssi()
+    }
+
+    fn poll_rssi_in_state(&mut self) -> Result<Option<i16>, Self::Error> {
+        self.inner.poll_rssi_in_state()
+    }
+
+    fn poll_rssi_packet(&mut self) -> Result<Option<i16>, Self::Error> {
+        self.inner.poll_rssi_packet()
+    }
+
+    fn rssi_scale(&self) -> RssiScale {
+        self.inner.rssi_scale()
+    }
+}
+
+impl<R> DeviceStatus for PersistentConfigRadio<R>
+where
+    R: DeviceStatus,
+{
+    type Error = R::Error;
+
+    fn get_status(&mut self) -> Result<DeviceStatusFlags, Self::Error> {
+        self.inner.get_status()
+    }
+
+    fn clear_status(&mut self, flags: DeviceStatusFlags) -> Result<(), Self::Error> {
+        self.inner.clear_status(flags)
+    }
+}
+
+impl<R> DelayNs for PersistentConfigRadio<R>
+where
+    R: DelayNs,
+{
+    fn delay_ns(&mut self, ns: u32) {
+        self.inner.delay_ns(ns)
+    }
+}
+
+/// Header flag for `ReliableLink` acknowledgement frames
+const RELIABLE_FLAG_ACK: u8 = 0x01;
+
+/// Length of the `ReliableLink` header (sequence number and flags)
+const RELIABLE_HEADER_LEN: usize = 2;
+
+/// Errors from `ReliableLink` operations
+#[derive(Clone, PartialEq, Debug)]
+pub enum ReliableError<E> {
+    /// Underlying radio error
+    Radio(BlockingError<E>),
+    /// No acknowledgement received after all retries
+    NoAck,
+    /// Received payload of `len` bytes exceeds the provided buffer
+    /// (the payload remains queued for a subsequent `poll_recv`)
+    BufferTooSmall { len: usize },
+}
+
+impl<E> From<BlockingError<E>> for ReliableError<E> {
+    fn from(e: BlockingError<E>) -> Self {
+        ReliableError::Radio(e)
+    }
+}
+
+/// Software acknowledgement and retry layer for reliable delivery over any radio
+///
+/// Frames are prefixed with a two byte header of sequence number and flags (`0x01` for ACK).
+/// Senders retransmit until a matching ACK is received, and receivers acknowledge each data
+/// frame and drop duplicates from retransmissions. This is independent of hardware `AutoAck`.
+/// Data frames received while awaiting an ACK are acknowledged and queued for `poll_recv`,
+/// restarting the ACK timeout.
+pub struct ReliableLink<R, I> {
+    inner: R,
+    options: BlockingOptions,
+    tx_seq: u8,
+    last_rx_seq: Option<u8>,
+    buff: Vec<u8>,
+    pending: VecDeque<(Vec<u8>, I)>,
+}
+
+impl<R, I, E> ReliableLink<R, I>
+where
+    R: Transmit<Error = E> + Receive<Info = I, Error = E> + DelayNs,
+    I: ReceiveInfo + std::fmt::Debug,
+    E: std::fmt::Debug,
+{
+    /// Wrap a radio with frames of up to `mtu` bytes, using `options` for transmit and ACK timeouts
+    pub fn new(inner: R, mtu: usize, options: BlockingOptions) -> Self {
+        Self {
+            inner,
+            options,
+            tx_seq: 0,
+            last_rx_seq: None,
+            buff: vec![0u8; mtu],
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Send data, retransmitting up to `max_retries` times until acknowledged
+    pub fn send_reliable(&mut self, data: &[u8], max_retries: u32) -> Result<(), ReliableError<E>> {
+        let seq = self.tx_seq;
+        self.tx_seq = self.tx_seq.wrapping_add(1);
+
+        let mut frame = Vec::with_capacity(data.len() + RELIABLE_HEADER_LEN);
+        frame.extend_from_slice(&[seq, 0]);
+        frame.extend_from_slice(data);
+
+        for attempt in 0..=max_retries {
+            debug!("Sending reliable frame {} (attempt {})", seq, attempt);
+
+            self.inner.do_transmit(&frame, self.options.clone())?;
+
+            // Await matching ACK, queueing data frames received in the meantime
+            loop {
+                match self.inner.do_receive(&mut self.buff, self.options.clone()) {
+                    Ok((n, _)) if self.buff[..n] == [seq, RELIABLE_FLAG_ACK] => return Ok(()),
+                    Ok((n, info)) if n >= RELIABLE_HEADER_LEN && self.buff[1] == 0 => {
+                        debug!("Queueing data frame received awaiting ACK for {}", seq);
+                        self.accept(n, info)?;
+                    }
+                    Ok(_) => debug!("Unexpected response to frame {}", seq),
+                    Err(BlockingError::Timeout) => {
+                        debug!("Timeout awaiting ACK for frame {}", seq);
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Err(ReliableError::NoAck)
+    }
+
+    /// Enter receive mode to listen for data frames
+    pub fn listen(&mut self) -> Result<(), ReliableError<E>> {
+        self.inner.start_receive().map_err(BlockingError::from)?;
+        Ok(())
+    }
+
+    /// Poll for received data frames, acknowledging them and returning the payload length and info
+    ///
+    /// Duplicate frames (retransmissions) are acknowledged but not returned. Receive mode is
+    /// re-entered after each frame. Payloads larger than `buff` are not truncated, returning
+    /// `ReliableError::BufferTooSmall` and remaining queued.
+    pub fn poll_recv(&mut self, buff: &mut [u8]) -> Result<Option<(usize, I)>, ReliableError<E>> {
+        if self.pending.is_empty()
+            && self
+                .inner
+                .check_receive_continuous()
+                .map_err(BlockingError::from)?
+        {
+            let (n, info) = self
+                .inner
+                .get_received(&mut self.buff)
+                .map_err(BlockingError::from)?;
+
+            // Ignore short and ACK frames
+            if n >= RELIABLE_HEADER_LEN && self.buff[1] & RELIABLE_FLAG_ACK == 0 {
+                self.accept(n, info)?;
+            }
+
+            self.listen()?;
+        }
+
+        let len = match self.pending.front() {
+            Some((payload, _)) => payload.len(),
+            None => return Ok(None),
+        };
+        if len > buff.len() {
+            return Err(ReliableError::BufferTooSmall { len });
+        }
+
+        let (payload, info) = self.pending.pop_front().unwrap();
+        buff[..len].copy_from_slice(&payload);
+
+        Ok(Some((len, info)))
+    }
+
+    /// Acknowledge the data frame of `n` bytes in the internal buffer, queueing new payloads
+    fn accept(&mut self, n: usize, info: I) -> Result<(), ReliableError<E>> {
+        let seq = self.buff[0];
+        let payload = self.buff[RELIABLE_HEADER_LEN..n].to_vec();
+
+        self.inner
+            .do_transmit(&[seq, RELIABLE_FLAG_ACK], self.options.clone())?;
+
+        if self.last_rx_seq == Some(seq) {
+            debug!("Dropping duplicate frame {}", seq);
+            return Ok(());
+        }
+        self.last_rx_seq = Some(seq);
+
+        self.pending.push_back((payload, info));
+
+        Ok(())
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Radio wrapper applying software address filtering when hardware filtering is disabled
+///
+/// When `ConfigOption::Promiscuous(true)` is set through this wrapper, received frames
+/// are filtered in software by matching `address` at `offset` bytes into the frame,
+/// with non-matching frames dropped and receive re-armed.
+pub struct FilteringRadio<R, I> {
+    inner: R,
+    address: Vec<u8>,
+    offset: usize,
+    buff_len: usize,
+    promiscuous: bool,
+    pending: Option<(Vec<u8>, I)>,
+}
+
+impl<R, I> FilteringRadio<R, I>
+where
+    R: Receive<Info = I>,
+    I: ReceiveInfo,
+{
+    /// Wrap a radio, filtering on `address` at `offset` in received frames of up to `buff_len` bytes
+    pub fn new(inner: R, address: &[u8], offset: usize, buff_len: usize) -> Self {
+        Self {
+            inner,
+            address: address.to_vec(),
+            offset,
+            buff_len,
+            promiscuous: false,
+            pending: None,
+        }
+    }
+
+    /// Check whether software filtering is active
+    pub fn filtering(&self) -> bool {
+        self.promiscuous
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.get(self.offset..self.offset + self.address.len()) == Some(&self.address[..])
+    }
+}
+
+impl<R, I> Receive for FilteringRadio<R, I>
+where
+    R: Receive<Info = I>,
+    I: ReceiveInfo,
+{
+    type Error = R::Error;
+    type Info = I;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        self.pending = None;
+        self.inner.start_receive()
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        if !self.inner.check_receive(restart)? {
+            return Ok(false);
+        }
+
+        if !self.promiscuous {
+            return Ok(true);
+        }
+
+        // Fetch and filter frame
+        let mut buff = vec![0u8; self.buff_len];
+        let (n, i) = self.inner.get_received(&mut buff)?;
+        buff.truncate(n);
+
+        if !self.matches(&buff) {
+            debug!("Dropping frame not matching address filter");
+            self.inner.start_receive()?;
+            return Ok(false);
+        }
+
+        self.pending = Some((buff, i));
+
+        Ok(true)
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        match self.pending.take() {
+            Some((d, i)) => {
+                let n = d.len().min(buff.len());
+                buff[..n].copy_from_slice(&d[..n]);
+                Ok((n, i))
+            }
+            None => self.inner.get_received(buff),
+        }
+    }
+}
+
+impl<R, I> Configure for FilteringRadio<R, I>
+where
+    R: Configure,
+{
+    type Error = R::Error;
+
+    fn set_option(&mut self, o: &ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.set_option(o)?;
+
+        if let ConfigOption::Promiscuous(p) = o {
+            self.promiscuous = *p;
+        }
+
+        Ok(())
+    }
+
+    fn get_option(&mut self, o: &mut ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.get_option(o)
+    }
+
+    fn effective_mtu(&mut self) -> Result<u16, ConfigError<Self::Error>> {
+        self.inner.effective_mtu()
+    }
+
+    fn frequency_range(&self) -> Option<(u32, u32)> {
+        self.inner.frequency_range()
+    }
+}
+
+/// Radio wrapper that invokes a callback on observed state transitions
+///
+/// Transitions are observed on successful `State::set_state` calls and on `State::get_state`
+/// results differing from the last known state. The callback receives the previous state
+/// (if known) and the new state.
+pub struct StateWatcher<R, S, F> {
+    inner: R,
+    last: Option<S>,
+    on_change: F,
+}
+
+impl<R, S, F> StateWatcher<R, S, F>
+where
+    R: State<State = S>,
+    S: RadioState + Clone + PartialEq,
+    F: FnMut(Option<&S>, &S),
+{
+    /// Wrap a radio, calling `on_change` on state transitions
+    pub fn new(inner: R, on_change: F) -> Self {
+        Self {
+            inner,
+            last: None,
+            on_change,
+        }
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    ///
+    /// Note that state changes made directly on the inner radio are not observed
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn observe(&mut self, state: &S) {
+        if self.last.as_ref() != Some(state) {
+            (self.on_change)(self.last.as_ref(), state);
+            self.last = Some(state.clone());
+        }
+    }
+}
+
+impl<R, S, F> State for StateWatcher<R, S, F>
+where
+    R: State<State = S>,
+    S: RadioState + Clone + PartialEq,
+    F: FnMut(Option<&S>, &S),
+{
+    type State = S;
+    type Error = R::Error;
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.inner.set_state(state.clone())?;
+        self.observe(&state);
+        Ok(())
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        let state = self.inner.get_state()?;
+        self.observe(&state);
+        Ok(state)
+    }
+}
+
+/// Errors from `PaControlledRadio` operations
+#[derive(Clone, PartialEq, Debug)]
+pub enum PaError<E, P> {
+    /// Underlying radio error
+    Radio(E),
+    /// PA enable pin error
+    Pin(P),
+}
+
+/// Radio wrapper controlling an external Power Amplifier (PA) enable pin
+///
+/// The PA enable pin is asserted prior to `Transmit::start_transmit`, and deasserted once
+/// `Transmit::check_transmit` reports completion or an error (or the transmission fails to start).
+/// Other operations are delegated to the wrapped radio, with all traits sharing the `PaError`
+/// error type so the wrapper may be used with the combined helpers (`do_echo`, `tx_then_rx`, etc.).
+pub struct PaControlledRadio<R, P> {
+    inner: R,
+    pin: P,
+}
+
+impl<R, P> PaControlledRadio<R, P>
+where
+    P: embedded_hal::digital::OutputPin,
+{
+    /// Wrap a radio, deasserting the PA enable pin
+    pub fn new(inner: R, mut pin: P) -> Result<Self, P::Error> {
+        pin.set_low()?;
+
+        Ok(Self { inner, pin })
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    ///
+    /// Note that transmissions made directly on the inner radio do not control the PA
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio and PA enable pin
+    pub fn into_inner(self) -> (R, P) {
+        (self.inner, self.pin)
+    }
+}
+
+impl<R, P> Transmit for PaControlledRadio<R, P>
+where
+    R: Transmit,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Error = PaError<R::Error, P::Error>;
+
+    const MTU: Option<usize> = R::MTU;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.pin.set_high().map_err(PaError::Pin)?;
+
+        if let Err(e) = self.inner.start_transmit(data) {
+            self.pin.set_low().map_err(PaError::Pin)?;
+            return Err(PaError::Radio(e));
+        }
+
+        Ok(())
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        let done = match self.inner.check_transmit() {
+            Ok(d) => d,
+            Err(e) => {
+                self.pin.set_low().map_err(PaError::Pin)?;
+                return Err(PaError::Radio(e));
+            }
+        };
+
+        if done {
+            self.pin.set_low().map_err(PaError::Pin)?;
+        }
+
+        Ok(done)
+    }
+
+    fn transmit_remaining(&mut self) -> Result<Option<Duration>, Self::Error> {
+        self.inner.transmit_remaining().map_err(PaError::Radio)
+    }
+
+    fn transmit_preamble(&mut self, duration: Duration) -> Result<(), ConfigError<Self::Error>> {
+        self.pin
+            .set_high()
+            .map_err(|e| ConfigError::Other(PaError::Pin(e)))?;
+
+        if let Err(e) = self.inner.transmit_preamble(duration) {
+            self.pin
+                .set_low()
+                .map_err(|e| ConfigError::Other(PaError::Pin(e)))?;
+            return Err(pa_config_err(e));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R, P> Receive for PaControlledRadio<R, P>
+where
+    R: Receive,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Error = PaError<R::Error, P::Error>;
+    type Info = R::Info;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        self.inner.start_receive().map_err(PaError::Radio)
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        self.inner.check_receive(restart).map_err(PaError::Radio)
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        self.inner.get_received(buff).map_err(PaError::Radio)
+    }
+
+    fn get_received_into_info(
+        &mut self,
+        info: &mut Self::Info,
+        buff: &mut [u8],
+    ) -> Result<usize, Self::Error> {
+        self.inner
+            .get_received_into_info(info, buff)
+            .map_err(PaError::Radio)
+    }
+
+    fn drain_received(
+        &mut self,
+        buff: &mut [u8],
+        each: impl FnMut(&[u8], &Self::Info),
+    ) -> Result<usize, Self::Error> {
+        self.inner
+            .drain_received(buff, each)
+            .map_err(PaError::Radio)
+    }
+
+    fn get_received_raw(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<(usize, Self::Info, bool), Self::Error> {
+        self.inner.get_received_raw(buff).map_err(PaError::Radio)
+    }
+
+    fn rx_overflow(&mut self) -> Result<bool, Self::Error> {
+        self.inner.rx_overflow().map_err(PaError::Radio)
+    }
+
+    fn last_rx_was_broadcast(&mut self) -> Result<Option<bool>, Self::Error> {
+        self.inner.last_rx_was_broadcast().map_err(PaError::Radio)
+    }
+}
+
+impl<R, P> Channel for PaControlledRadio<R, P>
+where
+    R: Channel,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Channel = R::Channel;
+    type Error = PaError<R::Error, P::Error>;
+
+    fn set_channel(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
+        self.inner.set_channel(channel).map_err(PaError::Radio)
+    }
+
+    fn set_channel_fast(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
+        self.inner.set_channel_fast(channel).map_err(PaError::Radio)
+    }
+}
+
+impl<R, P> Rssi for PaControlledRadio<R, P>
+where
+    R: Rssi,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Error = PaError<R::Error, P::Error>;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        self.inner.poll_rssi().map_err(PaError::Radio)
+    }
+
+    fn poll_rssi_in_state(&mut self) -> Result<Option<i16>, Self::Error> {
+        self.inner.poll_rssi_in_state().map_err(PaError::Radio)
+    }
+
+    fn poll_rssi_packet(&mut self) -> Result<Option<i16>, Self::Error> {
+        self.inner.poll_rssi_packet().map_err(PaError::Radio)
+    }
+
+    fn rssi_scale(&self) -> RssiScale {
+        self.inner.rssi_scale()
+    }
+}
+
+// Map inner radio configuration errors to `PaError`
+fn pa_config_err<E, P>(e: ConfigError<E>) -> ConfigError<PaError<E, P>> {
+    match e {
+        ConfigError::NotSupported => ConfigError::NotSupported,
+        ConfigError::OutOfRange => ConfigError::OutOfRange,
+        ConfigError::Other(e) => ConfigError::Other(PaError::Radio(e)),
+    }
+}
+
+impl<R, P> Configure for PaControlledRadio<R, P>
+where
+    R: Configure,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Error = PaError<R::Error, P::Error>;
+
+    fn set_option(&mut self, o: &ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.set_option(o).map_err(pa_config_err)
+    }
+
+    fn get_option(&mut self, o: &mut ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.get_option(o).map_err(pa_config_err)
+    }
+
+    fn apply_all(
+        &mut self,
+        options: &[ConfigOption],
+    ) -> Result<(), (usize, ConfigError<Self::Error>)> {
+        self.inner
+            .apply_all(options)
+            .map_err(|(i, e)| (i, pa_config_err(e)))
+    }
+
+    fn effective_mtu(&mut self) -> Result<u16, ConfigError<Self::Error>> {
+        self.inner.effective_mtu().map_err(pa_config_err)
+    }
+
+    fn frequency_range(&self) -> Option<(u32, u32)> {
+        self.inner.frequency_range()
+    }
+
+    fn snapshot(&mut self) -> Result<ConfigSnapshot, ConfigError<Self::Error>> {
+        self.inner.snapshot().map_err(pa_config_err)
+    }
+
+    fn restore(&mut self, snapshot: &ConfigSnapshot) -> Result<(), ConfigError<Self::Error>> {
+        self.inner.restore(snapshot).map_err(pa_config_err)
+    }
+}
+
+impl<R, P> State for PaControlledRadio<R, P>
+where
+    R: State,
+    P: embedded_hal::digital::OutputPin,
+{
+    type State = R::State;
+    type Error = PaError<R::Error, P::Error>;
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.inner.set_state(state).map_err(PaError::Radio)
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        self.inner.get_state().map_err(PaError::Radio)
+    }
+}
+
+impl<R, P> Power for PaControlledRadio<R, P>
+where
+    R: Power,
+    P: embedded_hal::digital::OutputPin,
+{
+    type Error = PaError<R::Error, P::Error>;
+
+    fn set_power(&mut self, power: i8) -> Result<(), Self::Error> {
+        self.inner.set_power(power).map_err(PaError::Radio)
+    }
+
+    fn power_range(&self) -> Option<(i8, i8)> {
+        self.inner.power_range()
+    }
+}
+
+impl<R, P> DelayNs for PaControlledRadio<R, P>
+where
+    R: DelayNs,
+{
+    fn delay_ns(&mut self, ns: u32) {
+        self.inner.delay_ns(ns)
+    }
+}
+
+/// Logical radio state tracked by `GuardedRadio`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GuardState {
+    /// State unknown (prior to any state change, or in a radio-specific state)
+    Unknown,
+    /// Radio idle
+    Idle,
+    /// Radio sleeping
+    Sleep,
+    /// Transmission in progress
+    Transmitting,
+    /// Radio in receive mode
+    Receiving,
+}
+
+/// Errors from `GuardedRadio` operations
+#[derive(Clone, PartialEq, Debug)]
+pub enum GuardError<E> {
+    /// Operation is not valid in the current logical state
+    IllegalTransition {
+        state: GuardState,
+        operation: &'static str,
+    },
+    /// Underlying radio error
+    Radio(E),
+}
+
+impl<E> From<E> for GuardError<E> {
+    fn from(e: E) -> Self {
+        GuardError::Radio(e)
+    }
+}
+
+/// Radio wrapper validating operations against the tracked logical state
+///
+/// Operations invalid for the current state (such as transmitting while asleep, or polling
+/// RSSI while not receiving) return `GuardError::IllegalTransition` without being forwarded
+/// to the radio. Operations are permitted while the state is unknown.
+pub struct GuardedRadio<R> {
+    inner: R,
+    state: GuardState,
+}
+
+impl<R> GuardedRadio<R> {
+    /// Wrap a radio, starting in the `Unknown` state
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            state: GuardState::Unknown,
+        }
+    }
+
+    /// Fetch the tracked logical state
+    pub fn state(&self) -> GuardState {
+        self.state
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    ///
+    /// Note that state changes made directly on the inner radio are not tracked
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn guard<E>(
+        &self,
+        operation: &'static str,
+        illegal: &[GuardState],
+    ) -> Result<(), GuardError<E>> {
+        if illegal.contains(&self.state) {
+            debug!("Illegal {} in state {:?}", operation, self.state);
+            return Err(GuardError::IllegalTransition {
+                state: self.state,
+                operation,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> State for GuardedRadio<R>
+where
+    R: State,
+    R::State: PartialEq,
+{
+    type State = R::State;
+    type Error = GuardError<R::Error>;
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        let next = if state == R::State::idle() {
+            GuardState::Idle
+        } else if state == R::State::sleep() {
+            GuardState::Sleep
+        } else if state.is_rx() {
+            GuardState::Receiving
+        } else if state.is_tx() {
+            GuardState::Transmitting
+        } else {
+            GuardState::Unknown
+        };
+
+        self.inner.set_state(state)?;
+        self.state = next;
+
+        Ok(())
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        Ok(self.inner.get_state()?)
+    }
+}
+
+impl<R> Transmit for GuardedRadio<R>
+where
+    R: Transmit,
+{
+    type Error = GuardError<R::Error>;
+
+    const MTU: Option<usize> = R::MTU;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.guard("start_transmit", &[GuardState::Sleep])?;
+
+        self.inner.start_transmit(data)?;
+        self.state = GuardState::Transmitting;
+
+        Ok(())
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        self.guard(
+            "check_transmit",
+            &[GuardState::Sleep, GuardState::Receiving],
+        )?;
+
+        let done = self.inner.check_transmit()?;
+        if done {
+            self.state = GuardState::Idle;
+        }
+
+        Ok(done)
+    }
+
+    fn transmit_remaining(&mut self) -> Result<Option<Duration>, Self::Error> {
+        Ok(self.inner.transmit_remaining()?)
+    }
+
+    fn transmit_preamble(&mut self, duration: Duration) -> Result<(), ConfigError<Self::Error>> {
+        self.guard("transmit_preamble", &[GuardState::Sleep])
+            .map_err(ConfigError::Other)?;
+
+        self.inner
+            .transmit_preamble(duration)
+            .map_err(|e| match e {
+                ConfigError::NotSupported => ConfigError::NotSupported,
+                ConfigError::OutOfRange => ConfigError::OutOfRange,
+                ConfigError::Other(e) => ConfigError::Other(GuardError::Radio(e)),
+            })?;
+        self.state = GuardState::Transmitting;
+
+        Ok(())
+    }
+}
+
+impl<R> Receive for GuardedRadio<R>
+where
+    R: Receive,
+{
+    type Error = GuardError<R::Error>;
+    type Info = R::Info;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        self.guard("start_receive", &[GuardState::Sleep])?;
+
+        self.inner.start_receive()?;
+        self.state = GuardState::Receiving;
+
+        Ok(())
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        self.guard(
+            "check_receive",
+            &[GuardState::Sleep, GuardState::Transmitting],
+        )?;
+
+        Ok(self.inner.check_receive(restart)?)
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        self.guard(
+            "get_received",
+            &[GuardState::Sleep, GuardState::Transmitting],
+        )?;
+
+        Ok(self.inner.get_received(buff)?)
+    }
+
+    fn get_received_raw(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<(usize, Self::Info, bool), Self::Error> {
+        self.guard(
+            "get_received_raw",
+            &[GuardState::Sleep, GuardState::Transmitting],
+        )?;
+
+        Ok(self.inner.get_received_raw(buff)?)
+    }
+
+    fn rx_overflow(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.inner.rx_overflow()?)
+    }
+
+    fn last_rx_was_broadcast(&mut self) -> Result<Option<bool>, Self::Error> {
+        Ok(self.inner.last_rx_was_broadcast()?)
+    }
+}
+
+impl<R> Rssi for GuardedRadio<R>
+where
+    R: Rssi,
+{
+    type Error = GuardError<R::Error>;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        self.guard(
+            "poll_rssi",
+            &[
+                GuardState::Idle,
+                GuardState::Sleep,
+                GuardState::Transmitting,
+            ],
+        )?;
+
+        Ok(self.inner.poll_rssi()?)
+    }
+
+    fn poll_rssi_in_state(&mut self) -> Result<Option<i16>, Self::Error> {
+        Ok(self.inner.poll_rssi_in_state()?)
+    }
+
+    fn poll_rssi_packet(&mut self) -> Result<Option<i16>, Self::Error> {
+        Ok(self.inner.poll_rssi_packet()?)
+    }
+
+    fn rssi_scale(&self) -> RssiScale {
+        self.inner.rssi_scale()
+    }
+}
+
+impl<R> DelayNs for GuardedRadio<R>
+where
+    R: DelayNs,
+{
+    fn delay_ns(&mut self, ns: u32) {
+        self.inner.delay_ns(ns)
+    }
+}
+
+/// Radio states for `NetRadio`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NetState {
+    Idle,
+    Sleep,
+    Receive,
+}
+
+impl RadioState for NetState {
+    fn idle() -> Self {
+        Self::Idle
+    }
+
+    fn sleep() -> Self {
+        Self::Sleep
+    }
+
+    fn is_rx(&self) -> bool {
+        *self == Self::Receive
+    }
+}
+
+/// Maximum datagram size received by `NetRadio`
+const NET_RADIO_MTU: usize = 2048;
+
+/// Radio implementation over UDP, for multi-process network simulation
+///
+/// Transmitted frames are sent as datagrams to the peer address, and frames are received
+/// from the bound socket while in receive mode. RSSI is simulated using a fixed value.
+pub struct NetRadio {
+    socket: std::net::UdpSocket,
+    peer: std::net::SocketAddr,
+    state: NetState,
+    rssi: i16,
+    received: Option<Vec<u8>>,
+}
+
+impl NetRadio {
+    /// Create a new radio bound to `bind`, transmitting to `peer`
+    pub fn new(
+        bind: std::net::SocketAddr,
+        peer: std::net::SocketAddr,
+    ) -> Result<Self, std::io::Error> {
+        let socket = std::net::UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            peer,
+            state: NetState::Idle,
+            rssi: -50,
+            received: None,
+        })
+    }
+
+    /// Fetch the bound socket address
+    pub fn local_addr(&self) -> Result<std::net::SocketAddr, std::io::Error> {
+        self.socket.local_addr()
+    }
+
+    /// Set the peer address for transmitted frames
+    pub fn set_peer(&mut self, peer: std::net::SocketAddr) {
+        self.peer = peer;
+    }
+
+    /// Set the simulated RSSI for received frames
+    pub fn set_rssi(&mut self, rssi: i16) {
+        self.rssi = rssi;
+    }
+}
+
+impl Transmit for NetRadio {
+    type Error = std::io::Error;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.socket.send_to(data, self.peer)?;
+        self.state = NetState::Idle;
+        Ok(())
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        // Datagrams are sent immediately
+        Ok(true)
+    }
+}
+
+impl Receive for NetRadio {
+    type Error = std::io::Error;
+    type Info = BasicInfo;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        self.state = NetState::Receive;
+        self.received = None;
+        Ok(())
+    }
+
+    fn check_receive(&mut self, _restart: bool) -> Result<bool, Self::Error> {
+        if self.state != NetState::Receive {
+            return Ok(false);
+        }
+
+        let mut buff = [0u8; NET_RADIO_MTU];
+        match self.socket.recv_from(&mut buff) {
+            Ok((n, _)) => {
+                self.received = Some(buff[..n].to_vec());
+                self.state = NetState::Idle;
+                Ok(true)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        let data = self.received.take().unwrap_or_default();
+        let n = data.len().min(buff.len());
+        buff[..n].copy_from_slice(&data[..n]);
+
+        Ok((n, BasicInfo::new(self.rssi, 0)))
+    }
+}
+
+impl State for NetRadio {
+    type State = NetState;
+    type Error = std::io::Error;
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.state = state;
+        Ok(())
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        Ok(self.state)
+    }
+}
+
+impl Rssi for NetRadio {
+    type Error = std::io::Error;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        Ok(self.rssi)
+    }
+}
+
+impl DelayNs for NetRadio {
+    fn delay_ns(&mut self, ns: u32) {
+        std::thread::sleep(std::time::Duration::from_nanos(ns as u64));
+    }
+}
+
+/// Software frequency hopping emulation for radios without hardware hop support
+///
+/// This implements `FhssEngine` over `Channel`, with `poll` to be called periodically
+/// (for example from a timer) to hop via `Channel::set_channel_fast` once the dwell time
+/// has elapsed. Each pass through the hop sequence visits every channel in the plan once,
+/// in a pseudo-random order derived from the sequence seed.
+pub struct SoftwareFhss<R, C> {
+    inner: R,
+    plan: Vec<C>,
+    dwell: std::time::Duration,
+    seed: u32,
+    rng: XorShift32,
+    sequence: Vec<usize>,
+    index: usize,
+    next_hop: Option<Instant>,
+}
+
+impl<R, C> SoftwareFhss<R, C>
+where
+    R: Channel<Channel = C>,
+    C: Clone + std::fmt::Debug,
+{
+    /// Wrap a radio, hopping is disabled until configured and started
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            plan: Vec::new(),
+            dwell: std::time::Duration::from_secs(0),
+            seed: 0,
+            rng: XorShift32::new(0),
+            sequence: Vec::new(),
+            index: 0,
+            next_hop: None,
+        }
+    }
+
+    /// Fetch the current channel, if hopping has been started
+    pub fn current(&self) -> Option<&C> {
+        self.sequence.get(self.index).map(|i| &self.plan[*i])
+    }
+
+    /// Start hopping at `now`, tuning to the first channel in the sequence (this has no
+    /// effect where the configured plan is empty)
+    ///
+    /// `now` should come from the same time source later passed to `poll`.
+    pub fn start_at(&mut self, now: Instant) -> Result<(), R::Error> {
+        if self.plan.is_empty() {
+            return Ok(());
+        }
+
+        self.rng = XorShift32::new(self.seed);
+        self.shuffle();
+        self.index = 0;
+
+        let ch = &self.plan[self.sequence[0]];
+        debug!("FHSS start on {:?}", ch);
+        self.inner.set_channel_fast(ch)?;
+
+        self.next_hop = Some(now + self.dwell);
+
+        Ok(())
+    }
+
+    /// Hop to the next channel in the sequence if the dwell time has elapsed by `now`,
+    /// returning true if a hop occurred
+    ///
+    /// The following hop is scheduled one dwell time after `now`, so a late poll
+    /// results in a single hop rather than a burst to catch up on missed slots.
+    pub fn poll(&mut self, now: Instant) -> Result<bool, R::Error> {
+        match self.next_hop {
+            Some(t) if now >= t => (),
+            _ => return Ok(false),
+        }
+
+        self.index += 1;
+        if self.index >= self.sequence.len() {
+            self.shuffle();
+            self.index = 0;
+        }
+
+        let ch = &self.plan[self.sequence[self.index]];
+        debug!("FHSS hop to {:?}", ch);
+        self.inner.set_channel_fast(ch)?;
+
+        self.next_hop = Some(now + self.dwell);
+
+        Ok(true)
+    }
+
+    /// Fetch a mutable reference to the wrapped radio
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner radio
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // Regenerate the hop sequence (Fisher-Yates shuffle of plan indices)
+    fn shuffle(&mut self) {
+        self.sequence = (0..self.plan.len()).collect();
+        for i in (1..self.sequence.len()).rev() {
+            let j = self.rng.next_below(i as u32 + 1) as usize;
+            self.sequence.swap(i, j);
+        }
+    }
+}
+
+impl<R, C> FhssEngine for SoftwareFhss<R, C>
+where
+    R: Channel<Channel = C>,
+    C: Clone + std::fmt::Debug,
+{
+    type Channel = C;
+    type Error = R::Error;
+
+    fn configure_fhss(
+        &mut self,
+        plan: &[Self::Channel],
+        dwell: std::time::Duration,
+        sequence_seed: u32,
+    ) -> Result<(), Self::Error> {
+        self.plan = plan.to_vec();
+        self.dwell = dwell;
+        self.seed = sequence_seed;
+        self.sequence.clear();
+        self.index = 0;
+        self.next_hop = None;
+
+        Ok(())
+    }
+
+    /// Start hopping from the current wall clock time, see `SoftwareFhss::start_at`
+    /// to supply the time source used with `poll`
+    fn start_fhss(&mut self) -> Result<(), Self::Error> {
+        self.start_at(Instant::now())
+    }
+
+    fn stop_fhss(&mut self) -> Result<(), Self::Error> {
+        self.next_hop = None;
+        Ok(())
+    }
+}
+
+/// Frame deduplication and reordering buffer, for gateways with multiple receivers
+/// or networks with retransmission
+///
+/// Frames are keyed on a caller-supplied sequence number. Duplicates seen within `window`
+/// are suppressed, and frames are released in sequence order. Gaps are skipped once the
+/// oldest pending frame has waited for `window`, or once `capacity` frames are pending.
+/// Frames pushed while `capacity` frames are pending are dropped, bounding memory use.
+/// The first frame pushed sets the initial expected sequence number.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FrameReorderBuffer {
+    window: std::time::Duration,
+    capacity: usize,
+    seen: std::collections::VecDeque<(u64, Instant)>,
+    pending: std::collections::BTreeMap<u64, (Vec<u8>, Instant)>,
+    next: Option<u64>,
+}
+
+impl FrameReorderBuffer {
+    /// Create a buffer with the provided duplicate / reorder window, holding up to `capacity` frames
+    pub fn new(window: std::time::Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity: capacity.max(1),
+            seen: Default::default(),
+            pending: Default::default(),
+            next: None,
+        }
+    }
+
+    /// Push a received frame, returning false if the frame was dropped as a duplicate,
+    /// as arriving after later frames were released, or as the buffer is full
+    pub fn push(&mut self, seq: u64, frame: &[u8], now: Instant) -> bool {
+        self.expire(now);
+
+        if self.seen.iter().any(|(s, _)| *s == seq) {
+            debug!("Dropping duplicate frame {}", seq);
+            return false;
+        }
+
+        let next = *self.next.get_or_insert(seq);
+        if seq < next {
+            debug!("Dropping late frame {} (expected {})", seq, next);
+            return false;
+        }
+
+        if self.pending.len() >= self.capacity {
+            debug!(
+                "Dropping frame {}, {} frames pending",
+                seq,
+                self.pending.len()
+            );
+            return false;
+        }
+
+        // Record frame identity, bounding memory by capacity
+        if self.seen.len() >= self.capacity {
+            self.seen.pop_front();
+        }
+        self.seen.push_back((seq, now));
+
+        self.pending.insert(seq, (frame.to_vec(), now));
+
+        true
+    }
+
+    /// Fetch the next frame in sequence order, if available
+    pub fn pop(&mut self, now: Instant) -> Option<(u64, Vec<u8>)> {
+        self.expire(now);
+
+        let (&first, (_, received)) = self.pending.iter().next()?;
+        let next = self.next.unwrap_or(first);
+
+        // Skip gaps once the oldest frame has waited for the window or the buffer is full
+        let stale = self
+            .pending
+            .values()
+            .any(|(_, t)| now.duration_since(*t) >= self.window);
+        if first != next && !stale && self.pending.len() < self.capacity {
+            return None;
+        }
+        if first != next {
+            debug!(
+                "Skipping missing frames {}..{} (waited {:?})",
+                next,
+                first,
+                now.duration_since(*received)
+            );
+        }
+
+        let (data, _) = self.pending.remove(&first)?;
+        self.next = Some(first + 1);
+
+        Some((first, data))
+    }
+
+    /// Number of frames pending release
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((_, t)) = self.seen.front() {
+            if now.duration_since(*t) < self.window {
+                break;
+            }
+            self.seen.pop_front();
+        }
+    }
+}
+
+/// Filter stage for `RxPipeline`, returning true to keep a frame
+pub type RxFilter<'a, I> = Box<dyn FnMut(&[u8], &I) -> bool + 'a>;
+
+/// Sink stage for `RxPipeline`, called with each forwarded frame
+pub type RxSink<'a, I> = Box<dyn FnMut(&[u8], &I) + 'a>;
+
+/// Counters for frames handled by an `RxPipeline`
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RxPipelineStats {
+    /// Frames received from the radio
+    pub received: usize,
+    /// Frames dropped by filter stages
+    pub filtered: usize,
+    /// Frames dropped as duplicates
+    pub duplicates: usize,
+    /// Frames passed to the sink
+    pub forwarded: usize,
+}
+
+/// Received frame processing pipeline, for composing gateway style receive handling
+///
+/// Frames are passed through each `filter` stage in the order added, then the optional
+/// `dedup` stage (dropping frames matching one of the last `window` forwarded frames),
+/// before being passed to the `sink`.
+pub struct RxPipeline<'a, I> {
+    filters: Vec<RxFilter<'a, I>>,
+    dedup: Option<(usize, std::collections::VecDeque<Vec<u8>>)>,
+    sink: Option<RxSink<'a, I>>,
+    stats: RxPipelineStats,
+}
+
+impl<'a, I> Default for RxPipeline<'a, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, I> RxPipeline<'a, I> {
+    /// Create an empty pipeline, forwarding all frames
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+            dedup: None,
+            sink: None,
+            stats: RxPipelineStats::default(),
+        }
+    }
+
+    /// Add a filter stage, frames for which `f` returns false are dropped
+    pub fn filter(mut self, f: impl FnMut(&[u8], &I) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Drop frames with contents matching any of the last `window` forwarded frames
+    pub fn dedup(mut self, window: usize) -> Self {
+        self.dedup = Some((window.max(1), Default::default()));
+        self
+    }
+
+    /// Set the sink stage, called with each frame passing the pipeline
+    pub fn sink(mut self, f: impl FnMut(&[u8], &I) + 'a) -> Self {
+        self.sink = Some(Box::new(f));
+        self
+    }
+
+    /// Fetch pipeline counters
+    pub fn stats(&self) -> &RxPipelineStats {
+        &self.stats
+    }
+
+    /// Pass a frame through the pipeline, returning true if it was forwarded to the sink
+    pub fn process(&mut self, data: &[u8], info: &I) -> bool {
+        self.stats.received += 1;
+
+        if !self.filters.iter_mut().all(|f| f(data, info)) {
+            self.stats.filtered += 1;
+            return false;
+        }
+
+        if let Some((window, recent)) = &mut self.dedup {
+            if recent.iter().any(|d| d == data) {
+                debug!("Dropping duplicate frame ({} bytes)", data.len());
+                self.stats.duplicates += 1;
+                return false;
+            }
+
+            if recent.len() >= *window {
+                recent.pop_front();
+            }
+            recent.push_back(data.to_vec());
+        }
+
+        if let Some(s) = &mut self.sink {
+            s(data, info);
+        }
+        self.stats.forwarded += 1;
+
+        true
+    }
+
+    /// Receive `count` frames from the radio, passing each through the pipeline
+    ///
+    /// Receive errors (including timeouts) are returned immediately, with counters
+    /// preserved for frames handled prior to the error.
+    pub fn run<T, E>(
+        &mut self,
+        radio: &mut T,
+        buff: &mut [u8],
+        count: usize,
+        options: BlockingOptions,
+    ) -> Result<&RxPipelineStats, BlockingError<E>>
+    where
+        T: Receive<Info = I, Error = E> + DelayNs,
+        I: std::fmt::Debug,
+        E: std::fmt::Debug,
+    {
+        for _ in 0..count {
+            let (n, i) = radio.do_receive(buff, options.clone())?;
+            self.process(&buff[..n], &i);
+        }
+
+        Ok(&self.stats)
+    }
+}
+
+/// Automatic frequency correction tracker for following a drifting peer
+///
+/// This uses the frequency error reported by `ReceiveInfo::freq_error_hz` to compute a
+/// corrected centre frequency, which the application should apply to the radio.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AfcTracker {
+    /// Current centre frequency in Hz
+    pub frequency_hz: u64,
+    /// Minimum (absolute) frequency error in Hz before a correction is applied
+    pub threshold_hz: u32,
+    /// Fraction of the measured error applied per correction (0.0 to 1.0)
+    pub damping: f32,
+}
+
+impl AfcTracker {
+    /// Create a new tracker with the provided centre frequency, threshold and damping
+    pub fn new(frequency_hz: u64, threshold_hz: u32, damping: f32) -> Self {
+        Self {
+            frequency_hz,
+            threshold_hz,
+            damping: damping.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Update the tracker with info from a received packet
+    ///
+    /// Returns the corrected centre frequency in Hz where the measured error exceeds
+    /// the threshold, or None if no correction is required (or no error was reported).
+    pub fn update<I: ReceiveInfo>(&mut self, info: &I) -> Option<u64> {
+        let error = info.freq_error_hz()?;
+        if error.unsigned_abs() <= self.threshold_hz {
+            return None;
+        }
+
+        // Apply damped correction towards the peer frequency
+        let correction = (error as f32 * self.damping) as i64;
+        if correction == 0 {
+            return None;
+        }
+
+        self.frequency_hz = (self.frequency_hz as i64 + correction) as u64;
+
+        debug!(
+            "AFC error {} Hz, corrected frequency {} Hz",
+            error, self.frequency_hz
+        );
+
+        Some(self.frequency_hz)
+    }
+}
+
+/// Thermal noise floor in dBm/Hz at room temperature
+const THERMAL_NOISE_DBM_HZ: f32 = -174.0;
+
+/// Estimate receiver sensitivity in dBm from the receive bandwidth, the SNR
+/// required for demodulation, and the receiver noise figure
+///
+/// This uses the standard `-174 + 10log10(BW) + NF + SNR` formula, see
+/// `lora_required_snr` for LoRa SNR values. For FSK the required SNR depends
+/// on the demodulator, with ~10 dB a typical value.
+pub fn estimate_sensitivity(bandwidth_hz: f32, required_snr_db: f32, noise_figure_db: f32) -> i16 {
+    let s = THERMAL_NOISE_DBM_HZ + 10.0 * bandwidth_hz.log10() + noise_figure_db + required_snr_db;
+    s.round() as i16
+}
+
+/// Required demodulation SNR in dB for LoRa spreading factors (SF6 to SF12)
+pub fn lora_required_snr(spreading_factor: u8) -> Option<f32> {
+    match spreading_factor {
+        6 => Some(-5.0),
+        7 => Some(-7.5),
+        8 => Some(-10.0),
+        9 => Some(-12.5),
+        10 => Some(-15.0),
+        11 => Some(-17.5),
+        12 => Some(-20.0),
+        _ => None,
+    }
+}
+
+/// Format a register dump (see `RawRegisters::dump`) as `address: value` lines
+pub fn format_register_dump(start: u8, data: &[u8]) -> String {
+    data.iter()
+        .enumerate()
+        .map(|(i, v)| format!("0x{:02x}: 0x{:02x}\n", start.wrapping_add(i as u8), v))
+        .collect()
+}
+
+/// Radio supply current in each state, in milliamps
+#[derive(Clone, PartialEq, Debug)]
+pub struct StateCurrents {
+    pub tx_ma: f32,
+    pub rx_ma: f32,
+    pub idle_ma: f32,
+    pub sleep_ma: f32,
+}
+
+/// Radio duty cycle profile, as time spent in each state per `period`
+///
+/// Time not spent transmitting, receiving, or idle is assumed to be spent sleeping.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DutyProfile {
+    pub period: std::time::Duration,
+    pub tx: std::time::Duration,
+    pub rx: std::time::Duration,
+    pub idle: std::time::Duration,
+}
+
+/// Estimate the average supply current in milliamps for the provided duty cycle profile
+///
+/// Battery life in hours may then be estimated as `capacity_mah / average_ma`.
+pub fn energy_model(currents: &StateCurrents, profile: &DutyProfile) -> f32 {
+    let period = profile.period.as_secs_f32();
+    if period <= 0.0 {
+        return 0.0;
+    }
+
+    let tx = profile.tx.as_secs_f32();
+    let rx = profile.rx.as_secs_f32();
+    let idle = profile.idle.as_secs_f32();
+    let sleep = (period - tx - rx - idle).max(0.0);
+
+    (currents.tx_ma * tx
+        + currents.rx_ma * rx
+        + currents.idle_ma * idle
+        + currents.sleep_ma * sleep)
+        / period
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_reorder_duplicates() {
+        let window = std::time::Duration::from_millis(100);
+        let mut b = FrameReorderBuffer::new(window, 8);
+        let t = Instant::now();
+
+        assert!(b.push(1, &[0x01], t));
+        assert!(!b.push(1, &[0x01], t + window / 2));
+        assert_eq!(b.pop(t), Some((1, vec![0x01])));
+
+        // Duplicates after window expiry are late rather than suppressed
+        assert!(!b.push(1, &[0x01], t + window * 2));
+
+        // Frames are released in order
+        assert!(b.push(3, &[0x03], t));
+        assert!(b.push(2, &[0x02], t));
+        assert_eq!(b.pop(t), Some((2, vec![0x02])));
+        assert_eq!(b.pop(t), Some((3, vec![0x03])));
+        assert_eq!(b.pop(t), None);
+    }
+
+    #[test]
+    fn frame_reorder_window_expiry() {
+        let window = std::time::Duration::from_millis(100);
+        let mut b = FrameReorderBuffer::new(window, 8);
+        let t = Instant::now();
+
+        assert!(b.push(1, &[0x01], t));
+        assert!(b.push(3, &[0x03], t));
+        assert_eq!(b.pop(t), Some((1, vec![0x01])));
+
+        // Frame 2 is missing, hold 3 until the window expires
+        assert_eq!(b.pop(t + window / 2), None);
+        assert_eq!(b.pop(t + window), Some((3, vec![0x03])));
+
+        // Frame 2 arriving after 3 is released is dropped
+        assert!(!b.push(2, &[0x02], t + window));
+        assert_eq!(b.pending(), 0);
+    }
+
+    #[test]
+    fn frame_reorder_capacity() {
+        let window = std::time::Duration::from_millis(100);
+        let mut b = FrameReorderBuffer::new(window, 2);
+        let t = Instant::now();
+
+        // Frames are dropped once capacity frames are pending
+        assert!(b.push(1, &[0x01], t));
+        assert!(b.push(3, &[0x03], t));
+        assert!(!b.push(4, &[0x04], t));
+        assert_eq!(b.pending(), 2);
+
+        // Space is available once frames are released
+        assert_eq!(b.pop(t), Some((1, vec![0x01])));
+        assert!(b.push(4, &[0x04], t));
+        assert_eq!(b.pending(), 2);
+    }
+
+    #[test]
+    fn pcap_rotate_path() {
+        assert_eq!(rotate_path("capture.pcap", 2), "capture-2.pcap");
+        assert_eq!(rotate_path("/tmp/out/capture", 0), "/tmp/out/capture-0");
+    }
+
+    #[test]
+    fn pcap_rotate_size() {
+        let dir = std::env::temp_dir().join(format!("radio-pcap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("capture.pcap").to_string_lossy().into_owned();
+
+        let mut pcap = PcapCapture::new(PcapOptions {
+            pcap_file: Some(file.clone()),
+            pcap_pipe: None,
+            pcap_rotate_size: Some(32),
+            pcap_rotate_time: None,
+        })
+        .unwrap();
+
+        for _ in 0..3 {
+            pcap.write(&[0xaa; 16]).unwrap();
+        }
+        drop(pcap);
+
+        // Each 32 byte record fills a file, so each write lands in a new one
+        for i in 0..3 {
+            assert!(std::path::Path::new(&rotate_path(&file, i)).exists());
+        }
+        assert!(!std::path::Path::new(&file).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn afc_tracker() {
+        #[derive(Debug, Default)]
+        struct Info(Option<i32>);
+
+        impl ReceiveInfo for Info {
+            fn rssi(&self) -> i16 {
+                0
+            }
+
+            fn freq_error_hz(&self) -> Option<i32> {
+                self.0
+            }
+        }
+
+        let mut afc = AfcTracker::new(915_000_000, 1_000, 0.5);
+
+        assert_eq!(afc.update(&Info(None)), None);
+        assert_eq!(afc.update(&Info(Some(-500))), None);
+        assert_eq!(afc.update(&Info(Some(4_000))), Some(915_002_000));
+        assert_eq!(afc.update(&Info(Some(-3_000))), Some(915_000_500));
+    }
+
+    #[test]
+    fn net_radio_loopback() {
+        let local = "127.0.0.1:0".parse().unwrap();
+
+        let mut a = NetRadio::new(local, local).unwrap();
+        let mut b = NetRadio::new(local, a.local_addr().unwrap()).unwrap();
+        a.set_peer(b.local_addr().unwrap());
+        b.set_rssi(-72);
+
+        let opts = BlockingOptions {
+            poll_interval: std::time::Duration::from_millis(1),
+            timeout: std::time::Duration::from_secs(1),
+        };
+
+        b.start_receive().unwrap();
+        a.do_transmit(&[0xaa, 0xbb, 0xcc], opts.clone()).unwrap();
+
+        let mut buff = [0u8; 16];
+        let (n, info) = b.do_receive(&mut buff, opts).unwrap();
+
+        assert_eq!(&buff[..n], &[0xaa, 0xbb, 0xcc]);
+        assert_eq!(info.rssi(), -72);
+    }
+
+    #[test]
+    fn sensitivity_lora() {
+        // Reference figures for SX127x at 125 kHz with a 6 dB noise figure
+        let s = |sf| estimate_sensitivity(125_000.0, lora_required_snr(sf).unwrap(), 6.0);
+
+        assert_eq!(s(7), -125);
+        assert_eq!(s(10), -132);
+        assert_eq!(s(12), -137);
+
+        assert_eq!(lora_required_snr(13), None);
+    }
+
+    #[test]
+    fn register_dump_format() {
+        assert_eq!(
+            format_register_dump(0xfe, &[0x12, 0x34]),
+            "0xfe: 0x12\n0xff: 0x34\n"
+        );
+    }
+
+    #[test]
+    fn energy_model_profile() {
+        let currents = StateCurrents {
+            tx_ma: 120.0,
+            rx_ma: 10.0,
+            idle_ma: 1.0,
+            sleep_ma: 0.001,
+        };
+
+        // 1s TX and 2s RX each minute
+        let profile = DutyProfile {
+            period: std::time::Duration::from_secs(60),
+            tx: std::time::Duration::from_secs(1),
+            rx: std::time::Duration::from_secs(2),
+            idle: std::time::Duration::ZERO,
+        };
+
+        let avg = energy_model(&currents, &profile);
+        assert!((avg - (120.0 + 20.0 + 0.057) / 60.0).abs() < 1e-4);
+
+        let idle = DutyProfile {
+            period: std::time::Duration::from_secs(1),
+            tx: std::time::Duration::ZERO,
+            rx: std::time::Duration::ZERO,
+            idle: std::time::Duration::from_secs(1),
+        };
+        assert_eq!(energy_model(&currents, &idle), 1.0);
+    }
 }
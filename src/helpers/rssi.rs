@@ -0,0 +1,49 @@
+//! RSSI / received power unit conversions, for comparison with RF test equipment
+//!
+//! Voltage based units assume a 50 Ω system impedance.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+/// System impedance in ohms used for voltage conversions
+pub const IMPEDANCE_OHMS: f32 = 50.0;
+
+/// Offset from dBm to dBµV into 50 Ω (`90 + 10log10(50)`, rounded)
+pub const DBUV_OFFSET_DB: i16 = 107;
+
+/// Convert power in dBm to voltage in dBµV (into 50 Ω)
+pub fn dbm_to_dbuv(dbm: i16) -> i16 {
+    dbm.saturating_add(DBUV_OFFSET_DB)
+}
+
+/// Convert voltage in dBµV (into 50 Ω) to power in dBm
+pub fn dbuv_to_dbm(dbuv: i16) -> i16 {
+    dbuv.saturating_sub(DBUV_OFFSET_DB)
+}
+
+/// Convert power in dBm to RMS voltage in µV (into 50 Ω)
+pub fn dbm_to_uv(dbm: i16) -> f32 {
+    let watts = 10f32.powf((dbm as f32 - 30.0) / 10.0);
+    (watts * IMPEDANCE_OHMS).sqrt() * 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dbuv_conversions() {
+        assert_eq!(dbm_to_dbuv(0), 107);
+        assert_eq!(dbm_to_dbuv(-107), 0);
+        assert_eq!(dbuv_to_dbm(107), 0);
+        assert_eq!(dbuv_to_dbm(dbm_to_dbuv(-90)), -90);
+    }
+
+    #[test]
+    fn uv_conversions() {
+        // 0 dBm into 50 Ω is ~223.6 mV
+        assert!((dbm_to_uv(0) - 223_607.0).abs() < 1.0);
+        // -107 dBm into 50 Ω is ~1 µV (0 dBµV)
+        assert!((dbm_to_uv(-107) - 1.0).abs() < 0.01);
+    }
+}
@@ -11,9 +11,19 @@
 
 use core::convert::TryFrom;
 use core::fmt::Debug;
+use core::time::Duration;
 
+use embedded_hal::delay::DelayNs;
+
+pub mod addr;
 pub mod blocking;
 pub mod config;
+pub mod fcs;
+pub mod fragment;
+pub mod linktest;
+pub mod packet;
+pub mod rng;
+pub mod stats;
 
 #[cfg(feature = "helpers")]
 pub mod helpers;
@@ -33,6 +43,12 @@ pub trait Transmit {
     /// Radio error
     type Error: Debug;
 
+    /// Maximum transmit payload length in bytes, if known
+    ///
+    /// Where set, blocking helpers reject larger payloads with `BlockingError::PayloadTooLarge`
+    /// rather than relying on driver-defined truncation.
+    const MTU: Option<usize> = None;
+
     /// Start sending a packet on the provided channel
     ///
     /// Returns an error if send was not started
@@ -42,6 +58,41 @@ pub trait Transmit {
     ///
     /// Returns true for send complete, false otherwise
     fn check_transmit(&mut self) -> Result<bool, Self::Error>;
+
+    /// Estimate the time remaining for an in-progress transmission
+    ///
+    /// Radios able to report transmit progress (such as via a byte counter) return
+    /// `Some(remaining)`, the default implementation returns `None` (unknown).
+    fn transmit_remaining(&mut self) -> Result<Option<Duration>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Start transmitting an extended preamble only, for `duration`, to wake duty-cycled
+    /// (wake-on-radio) receivers prior to sending a packet
+    ///
+    /// Completion should be polled using `check_transmit`. Radios without extended preamble
+    /// support return `ConfigError::NotSupported` (the default implementation).
+    fn transmit_preamble(
+        &mut self,
+        _duration: Duration,
+    ) -> Result<(), config::ConfigError<Self::Error>> {
+        Err(config::ConfigError::NotSupported)
+    }
+}
+
+/// ScheduledTransmit trait for radios that can transmit packets at a scheduled time
+///
+/// This offloads precise transmit timing to hardware, for use in slotted (TDMA) protocols.
+/// Completion should be polled using `Transmit::check_transmit`.
+pub trait ScheduledTransmit {
+    /// Radio error
+    type Error: Debug;
+
+    /// Schedule a packet for transmission at the provided radio-local time in microseconds
+    ///
+    /// Radios without hardware scheduling should return a `NotSupported` error,
+    /// see `BlockingTransmit::do_transmit_at` for a delay-based emulation.
+    fn transmit_at(&mut self, data: &[u8], when: u64) -> Result<(), Self::Error>;
 }
 
 /// Receive trait for radios that can receive packets
@@ -68,11 +119,99 @@ pub trait Receive {
     /// This returns true for received, false for not received, or the provided error
     fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error>;
 
+    /// Check for reception without restarting on (recoverable) errors
+    ///
+    /// This is equivalent to `check_receive(false)`, the radio is left in its current state
+    /// on error conditions (such as invalid CRC) and must be restarted by the caller.
+    fn check_receive_once(&mut self) -> Result<bool, Self::Error> {
+        self.check_receive(false)
+    }
+
+    /// Check for reception, restarting receive on (recoverable) errors
+    ///
+    /// This is equivalent to `check_receive(true)`, for continuous reception where the radio
+    /// should re-enter receive mode on error conditions (such as invalid CRC).
+    fn check_receive_continuous(&mut self) -> Result<bool, Self::Error> {
+        self.check_receive(true)
+    }
+
     /// Fetch a received packet if rx is complete
     ///
     /// This copies received data into the provided buffer and returns the number of bytes received
     /// as well as information about the received packet
     fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error>;
+
+    /// Fetch a received packet into a caller provided info object, returning the number of bytes received
+    ///
+    /// This allows rich info objects to be reused between packets. The default implementation
+    /// calls `get_received` and overwrites `info`, radios may override this to update `info`
+    /// in place.
+    fn get_received_into_info(
+        &mut self,
+        info: &mut Self::Info,
+        buff: &mut [u8],
+    ) -> Result<usize, Self::Error> {
+        let (n, i) = self.get_received(buff)?;
+        *info = i;
+
+        Ok(n)
+    }
+
+    /// Fetch all buffered received packets, calling `each` with the data and info of each
+    /// packet and returning the number of packets received
+    ///
+    /// The default implementation fetches a single packet with `get_received`, radios with
+    /// multi-frame FIFOs should override this to avoid leaving packets in the FIFO.
+    fn drain_received(
+        &mut self,
+        buff: &mut [u8],
+        mut each: impl FnMut(&[u8], &Self::Info),
+    ) -> Result<usize, Self::Error> {
+        let (n, i) = self.get_received(buff)?;
+        each(&buff[..n], &i);
+
+        Ok(1)
+    }
+
+    /// Fetch a received packet along with its CRC validity, including invalid packets
+    ///
+    /// This allows frames failing CRC checks to be inspected for debugging, and should be used
+    /// with `check_receive(false)` so invalid frames are not discarded by a receive restart.
+    /// The default implementation calls `get_received` and reports all packets as valid.
+    fn get_received_raw(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<(usize, Self::Info, bool), Self::Error> {
+        let (n, i) = self.get_received(buff)?;
+        Ok((n, i, true))
+    }
+
+    /// Fetch a received packet into a fixed capacity `Packet` buffer
+    fn get_received_packet<const N: usize>(
+        &mut self,
+    ) -> Result<(packet::Packet<N>, Self::Info), Self::Error> {
+        let mut p = packet::Packet::new();
+        let (n, i) = self.get_received(p.buffer_mut())?;
+        p.set_len(n.min(N));
+
+        Ok((p, i))
+    }
+
+    /// Check for receive buffer overflow (FIFO overrun)
+    ///
+    /// Returns true if received data has been lost since the last check, allowing applications
+    /// to detect and count dropped packets. Radios that cannot detect overflow return false.
+    fn rx_overflow(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    /// Check whether the last received packet was a broadcast (true) or unicast (false) frame
+    ///
+    /// This is intended for radios with hardware address filtering that report the type of
+    /// address match. Radios that cannot determine this return `Ok(None)`.
+    fn last_rx_was_broadcast(&mut self) -> Result<Option<bool>, Self::Error> {
+        Ok(None)
+    }
 }
 
 /// ReceiveInfo trait for receive information objects
@@ -80,7 +219,30 @@ pub trait Receive {
 /// This sup[ports the constraint of generic `Receive::Info`, allowing generic middleware
 /// to access the rssi of received packets
 pub trait ReceiveInfo: Debug + Default {
+    /// Packet RSSI of the received packet in dBm, as latched by the radio on packet sync
+    ///
+    /// This is distinct from the instantaneous channel RSSI returned by `Rssi::poll_rssi`
     fn rssi(&self) -> i16;
+
+    /// Link Quality Indicator (LQI) of the received packet, if supported
+    fn lqi(&self) -> Option<u16> {
+        None
+    }
+
+    /// Signal to Noise Ratio (SNR) of the received packet in dB, if supported
+    fn snr(&self) -> Option<i16> {
+        None
+    }
+
+    /// Receive timestamp of the packet in microseconds, if supported
+    fn timestamp(&self) -> Option<u64> {
+        None
+    }
+
+    /// Measured frequency error of the received packet in Hz (received - expected), if supported
+    fn freq_error_hz(&self) -> Option<i32> {
+        None
+    }
 }
 
 /// Default / Standard packet information structure for radio devices that provide only rssi
@@ -96,8 +258,8 @@ pub struct BasicInfo {
 impl Default for BasicInfo {
     fn default() -> Self {
         Self {
-            rssi: core::i16::MIN,
-            lqi: core::u16::MIN,
+            rssi: i16::MIN,
+            lqi: u16::MIN,
         }
     }
 }
@@ -108,11 +270,21 @@ impl BasicInfo {
     }
 }
 
+impl core::fmt::Display for BasicInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "rssi={}dBm lqi={}", self.rssi, self.lqi)
+    }
+}
+
 /// Default / Standard radio channel object for radio devices with simple integer channels
 impl ReceiveInfo for BasicInfo {
     fn rssi(&self) -> i16 {
         self.rssi
     }
+
+    fn lqi(&self) -> Option<u16> {
+        Some(self.lqi)
+    }
 }
 
 /// Default / Standard radio channel object for radio devices with integer channels
@@ -140,6 +312,43 @@ pub trait Channel {
 
     /// Set the radio channel for future transmit and receive operations
     fn set_channel(&mut self, channel: &Self::Channel) -> Result<(), Self::Error>;
+
+    /// Set the radio channel, using a fast retune where possible for frequency hopping
+    ///
+    /// Radios able to hop without a full PLL relock should perform a quick retune where the
+    /// frequency delta from the current channel is within their (driver defined) fast-hop
+    /// threshold, falling back to a full retune otherwise. The default implementation calls
+    /// `set_channel`.
+    fn set_channel_fast(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
+        self.set_channel(channel)
+    }
+}
+
+/// FhssEngine trait for radios with hardware (timer driven) frequency hopping
+///
+/// Once started the radio hops between the channels of the configured plan every `dwell`
+/// without MCU intervention. See `helpers::SoftwareFhss` for a software emulation over
+/// `Channel` for radios without hardware hop support.
+pub trait FhssEngine {
+    /// Channel information
+    type Channel: Debug;
+    /// Radio error type
+    type Error: Debug;
+
+    /// Configure the hop plan, the dwell time on each channel, and the seed for the
+    /// pseudo-random hop sequence (peers must use the same plan and seed)
+    fn configure_fhss(
+        &mut self,
+        plan: &[Self::Channel],
+        dwell: Duration,
+        sequence_seed: u32,
+    ) -> Result<(), Self::Error>;
+
+    /// Start hopping from the beginning of the configured sequence
+    fn start_fhss(&mut self) -> Result<(), Self::Error>;
+
+    /// Stop hopping, remaining on the current channel
+    fn stop_fhss(&mut self) -> Result<(), Self::Error>;
 }
 
 /// Power trait for configuring radio power
@@ -148,20 +357,157 @@ pub trait Power {
     type Error: Debug;
 
     /// Set the radio power in dBm
+    ///
+    /// For radios with multiple PAs the valid range depends on the selected PA
+    /// (see `ConfigOption::PaSelect`)
     fn set_power(&mut self, power: i8) -> Result<(), Self::Error>;
+
+    /// Fetch the supported (minimum, maximum) transmit power range in dBm, if known
+    fn power_range(&self) -> Option<(i8, i8)> {
+        None
+    }
+
+    /// Set the radio power as a percentage (0-100) of the supported power range
+    ///
+    /// Returns the power applied in dBm, or None if the power range is not known
+    /// (in which case the power is not changed).
+    fn set_power_pct(&mut self, pct: u8) -> Result<Option<i8>, Self::Error> {
+        let (min, max) = match self.power_range() {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        // Map percentage across range, rounding to the nearest dBm
+        let pct = pct.min(100) as i32;
+        let span = max as i32 - min as i32;
+        let power = (min as i32 + (span * pct + 50) / 100) as i8;
+
+        self.set_power(power)?;
+
+        Ok(Some(power))
+    }
 }
 
 /// Rssi trait allows polling for RSSI on the current channel
 ///
 /// Note that the radio should be in receive mode prior to polling for this.
+/// Channel RSSI is an instantaneous measurement of the channel (including noise and
+/// interference), as distinct from packet RSSI latched on packet sync (see `ReceiveInfo::rssi`).
 pub trait Rssi {
     /// Radio error
     type Error: Debug;
 
-    /// Fetch the current RSSI value from the radio
+    /// Fetch the current (instantaneous channel) RSSI value from the radio
     /// Note that the radio MUST be in RX mode (or capable of measuring RSSI) when this is called
     /// or an error should be returned
     fn poll_rssi(&mut self) -> Result<i16, Self::Error>;
+
+    /// Fetch the packet RSSI latched by the radio on packet sync
+    ///
+    /// This is the measurement reported by `ReceiveInfo::rssi` for received packets.
+    /// Radios without latched packet RSSI return `None` (the default), use `poll_rssi`
+    /// for channel RSSI.
+    fn poll_rssi_packet(&mut self) -> Result<Option<i16>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Fetch the instantaneous RSSI value without changing the radio state
+    ///
+    /// Radios that can measure RSSI in their current state (for example while idle or
+    /// transmitting) should return `Some(rssi)`, radios that can only measure RSSI in RX mode
+    /// should return `None` when not receiving. The default implementation always returns `None`.
+    fn poll_rssi_in_state(&mut self) -> Result<Option<i16>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Fetch the scale of RSSI values returned by this radio, for normalising readings
+    /// across radios (defaults to dBm in 1 dB steps)
+    fn rssi_scale(&self) -> RssiScale {
+        RssiScale::Dbm
+    }
+}
+
+/// Scale of RSSI values reported by a radio
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RssiScale {
+    /// dBm in 1 dB steps
+    #[default]
+    Dbm,
+    /// Linearly scaled raw values, where `dBm = raw * step_cdb / 100 + offset_dbm`
+    /// (for example half dB steps have `step_cdb = 50`)
+    Scaled { step_cdb: u16, offset_dbm: i16 },
+}
+
+impl RssiScale {
+    /// Convert a raw RSSI value in this scale to dBm (rounding towards zero)
+    pub fn to_dbm(&self, raw: i16) -> i16 {
+        match self {
+            RssiScale::Dbm => raw,
+            RssiScale::Scaled {
+                step_cdb,
+                offset_dbm,
+            } => (raw as i32 * *step_cdb as i32 / 100 + *offset_dbm as i32) as i16,
+        }
+    }
+}
+
+/// Sampler trait for capturing fixed-rate RSSI snapshots, for signal and interference analysis
+///
+/// The default implementation emulates sampling via `Rssi::poll_rssi` and the radio delay,
+/// radios with hardware sampling support should override this for accurate timing.
+/// As with `poll_rssi` the radio should be in receive mode prior to capture.
+pub trait Sampler: Rssi + DelayNs {
+    /// Fill `samples` with RSSI values taken every `interval`, returning the number of samples captured
+    fn capture_rssi(
+        &mut self,
+        samples: &mut [i16],
+        interval: Duration,
+    ) -> Result<usize, <Self as Rssi>::Error> {
+        for (i, s) in samples.iter_mut().enumerate() {
+            if i > 0 {
+                blocking::delay_us_long(
+                    self,
+                    u64::try_from(interval.as_micros()).unwrap_or(u64::MAX),
+                );
+            }
+
+            *s = self.poll_rssi()?;
+        }
+
+        Ok(samples.len())
+    }
+}
+
+/// TxPowerMonitor trait for radios able to measure output power during transmission
+///
+/// This samples a loopback or monitor path, for example to visualise the PA ramp envelope,
+/// and is distinct from receive-side RSSI sampling (see `Sampler`).
+pub trait TxPowerMonitor {
+    /// Radio error
+    type Error: Debug;
+
+    /// Fill `samples` with power measurements in dBm taken every `interval` during an active
+    /// transmission, returning the number of samples captured
+    fn monitor_tx(&mut self, samples: &mut [i16], interval: Duration)
+        -> Result<usize, Self::Error>;
+}
+
+/// Diagnostics trait for reading back radio configuration
+pub trait Diagnostics {
+    /// Radio error
+    type Error: Debug;
+
+    /// Fetch the current packet framing configuration (CRC, whitening, and length mode)
+    fn get_packet_config(&mut self) -> Result<config::PacketConfig, Self::Error>;
+
+    /// Fetch the receiver sensitivity in dBm for the current configuration, if known
+    ///
+    /// Radios unable to report sensitivity return `None` (the default), in which case
+    /// applications may fall back to `helpers::estimate_sensitivity`.
+    fn sensitivity(&mut self) -> Result<Option<i16>, Self::Error> {
+        Ok(None)
+    }
 }
 
 /// State trait for configuring and reading radio states
@@ -185,6 +531,67 @@ pub trait RadioState: Debug {
     fn idle() -> Self;
 
     fn sleep() -> Self;
+
+    /// Check whether the state is a receive state (defaults to false for custom states)
+    fn is_rx(&self) -> bool {
+        false
+    }
+
+    /// Check whether the state is a transmit state (defaults to false for custom states)
+    fn is_tx(&self) -> bool {
+        false
+    }
+}
+
+/// Standard radio states, for drivers without chip-specific states to use directly or map to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StandardState {
+    /// Low power sleep
+    Sleep,
+    /// Idle / standby
+    Idle,
+    /// Receiving
+    Rx,
+    /// Transmitting
+    Tx,
+    /// Channel Activity Detection (CAD)
+    Cad,
+}
+
+impl RadioState for StandardState {
+    fn idle() -> Self {
+        Self::Idle
+    }
+
+    fn sleep() -> Self {
+        Self::Sleep
+    }
+
+    fn is_rx(&self) -> bool {
+        *self == Self::Rx
+    }
+
+    fn is_tx(&self) -> bool {
+        *self == Self::Tx
+    }
+}
+
+/// Shutdown trait for radios supporting a cold shutdown state
+///
+/// This is distinct from `RadioState::sleep()`, shutdown invalidates the radio configuration
+/// in exchange for minimum current draw, so the radio must be reconfigured on wakeup.
+pub trait Shutdown {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Enter the lowest power shutdown state
+    fn shutdown(&mut self) -> Result<(), Self::Error>;
+
+    /// Wake the radio from shutdown
+    ///
+    /// Implementations must reconfigure the radio, as configuration is lost on shutdown
+    fn wakeup(&mut self) -> Result<(), Self::Error>;
 }
 
 /// Busy trait for checking whether the radio is currently busy
@@ -198,6 +605,131 @@ pub trait Busy {
     fn is_busy(&mut self) -> Result<bool, Self::Error>;
 }
 
+/// Cad trait for radios supporting Channel Activity Detection (CAD)
+///
+/// CAD detects the presence of a (LoRa) preamble in a fraction of the time and power
+/// of a full receive, allowing low-power listening (see `helpers::cad_receive`).
+pub trait Cad {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Start channel activity detection
+    fn start_cad(&mut self) -> Result<(), Self::Error>;
+
+    /// Check for CAD completion, returning `Some(true)` if activity was detected,
+    /// `Some(false)` if the channel was clear, or `None` if detection is ongoing
+    fn check_cad(&mut self) -> Result<Option<bool>, Self::Error>;
+}
+
+/// PreambleDetect trait for radios reporting preamble and sync word detection
+pub trait PreambleDetect {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Check whether a preamble has been detected since receive was started
+    fn preamble_detected(&mut self) -> Result<bool, Self::Error>;
+
+    /// Fetch preamble and sync word detection timing for the last received packet
+    ///
+    /// Radios unable to report detection timing return `None` (the default).
+    fn detection_timing(&mut self) -> Result<Option<DetectionTiming>, Self::Error> {
+        Ok(None)
+    }
+}
+
+/// Preamble and sync word detection timing for a received packet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectionTiming {
+    /// Duration of the detected preamble
+    pub preamble: Duration,
+    /// Time from sync word detection to the end of the packet
+    pub sync_offset: Duration,
+}
+
+/// DeviceStatus trait for radios exposing a device status / error register
+///
+/// This supports diagnosing failed operations beyond the generic radio error type.
+pub trait DeviceStatus {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Fetch the current device status flags
+    fn get_status(&mut self) -> Result<DeviceStatusFlags, Self::Error>;
+
+    /// Clear the provided (latched) status flags
+    fn clear_status(&mut self, flags: DeviceStatusFlags) -> Result<(), Self::Error>;
+}
+
+/// Device status flags reported by `DeviceStatus`
+///
+/// Radios should map the relevant fields of their status registers to these flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeviceStatusFlags(u16);
+
+impl DeviceStatusFlags {
+    /// The last command failed or was rejected
+    pub const COMMAND_ERROR: Self = Self(1 << 0);
+    /// The synthesizer PLL is locked
+    pub const PLL_LOCK: Self = Self(1 << 1);
+    /// Data is available to be read
+    pub const DATA_READY: Self = Self(1 << 2);
+    /// Calibration failed
+    pub const CALIBRATION_ERROR: Self = Self(1 << 3);
+    /// FIFO overflow or underflow occurred
+    pub const FIFO_ERROR: Self = Self(1 << 4);
+    /// The device has reset (or browned out) and lost its configuration
+    pub const RESET: Self = Self(1 << 5);
+
+    /// Create an empty flag set
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Create a flag set from raw bits
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Fetch raw flag bits
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Check whether no flags are set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check whether all of the provided flags are set
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for DeviceStatusFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for DeviceStatusFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Fifo trait for radios exposing FIFO fill levels, for streaming payloads larger than the FIFO
+pub trait Fifo {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Fetch the current FIFO levels as `(rx_bytes, tx_space)`, the number of received bytes
+    /// available to read and the free space available for transmit data
+    fn fifo_level(&mut self) -> Result<(usize, usize), Self::Error>;
+}
+
 /// Interrupts trait allows for reading interrupt state from the device,
 /// as well as configuring interrupt pins.
 ///
@@ -214,6 +746,41 @@ pub trait Interrupts {
     fn get_interrupts(&mut self, clear: bool) -> Result<Self::Irq, Self::Error>;
 }
 
+/// IrqFlags trait for interrupt status types, allowing generic code to check
+/// common interrupt causes without knowledge of radio-specific flag layouts
+pub trait IrqFlags: Debug {
+    /// Transmission complete
+    fn tx_done(&self) -> bool;
+
+    /// Reception complete
+    fn rx_done(&self) -> bool;
+
+    /// Received packet failed CRC check
+    fn crc_error(&self) -> bool {
+        false
+    }
+
+    /// Operation timed out
+    fn timeout(&self) -> bool {
+        false
+    }
+}
+
+/// Nvm trait provides access to on-chip non-volatile memory (EEPROM / OTP)
+///
+/// This is intended for reading and writing calibration data or device identity,
+/// radios without non-volatile memory should not implement this trait.
+pub trait Nvm {
+    /// Radio error type
+    type Error: Debug;
+
+    /// Read non-volatile memory from the provided address into `buf`
+    fn read_nvm(&mut self, addr: u16, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Write `data` to non-volatile memory at the provided address
+    fn write_nvm(&mut self, addr: u16, data: &[u8]) -> Result<(), Self::Error>;
+}
+
 /// Register contains the address and value of a register.
 ///
 /// It is primarily intended as a type constraint for the [Registers] trait.
@@ -248,6 +815,152 @@ pub trait Registers<Word> {
         self.write_register(updated)?;
         Ok(updated)
     }
+
+    /// Apply a group of register writes, rolling back to prior values on failure
+    ///
+    /// Writes made through the provided `RegisterTransaction` record the prior register
+    /// value (up to `N` writes), which are restored in reverse order if `f` returns an error.
+    /// Radios with shadow registers may override this to apply writes atomically.
+    fn with_transaction<const N: usize, F>(
+        &mut self,
+        f: F,
+    ) -> Result<(), TransactionError<Self::Error>>
+    where
+        Self: Sized,
+        Word: Copy,
+        F: FnOnce(
+            &mut RegisterTransaction<'_, Self, Word, N>,
+        ) -> Result<(), TransactionError<Self::Error>>,
+    {
+        let mut t = RegisterTransaction {
+            regs: self,
+            undo: [None; N],
+            count: 0,
+        };
+
+        let res = f(&mut t);
+
+        if res.is_err() {
+            t.rollback()?;
+        }
+
+        res
+    }
+}
+
+/// RawRegisters trait provides address based register access, for debugging and register dumps
+///
+/// Unlike `Registers` this is untyped, and is intended for diagnostics rather than driver logic.
+pub trait RawRegisters {
+    type Error: Debug;
+
+    /// Read a single register by address
+    fn read_raw(&mut self, address: u8) -> Result<u8, Self::Error>;
+
+    /// Read consecutive registers starting at `start` to fill `out`
+    ///
+    /// The default implementation falls back to single reads,
+    /// radios supporting burst reads should override this.
+    fn read_burst(&mut self, start: u8, out: &mut [u8]) -> Result<(), Self::Error> {
+        for (i, v) in out.iter_mut().enumerate() {
+            *v = self.read_raw(start.wrapping_add(i as u8))?;
+        }
+
+        Ok(())
+    }
+
+    /// Dump `count` registers starting at `start` into `out`
+    ///
+    /// Returns the number of registers read, which is limited to the length of `out`
+    fn dump(&mut self, start: u8, count: u8, out: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = out.len().min(count as usize);
+        self.read_burst(start, &mut out[..n])?;
+        Ok(n)
+    }
+}
+
+/// Errors from register transactions
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransactionError<E> {
+    /// Transaction exceeded the undo log capacity
+    Full,
+    /// Register access error
+    Inner(E),
+    /// Register access error while rolling back, register state is inconsistent
+    RollbackFailed(E),
+    /// Recorded register value could not be converted back while rolling back,
+    /// register state is inconsistent
+    RollbackInvalid,
+}
+
+impl<E> From<E> for TransactionError<E> {
+    fn from(e: E) -> Self {
+        TransactionError::Inner(e)
+    }
+}
+
+/// Function restoring a previously recorded register value
+type RestoreFn<T, Word> =
+    fn(&mut T, Word) -> Result<(), TransactionError<<T as Registers<Word>>::Error>>;
+
+/// Register transaction recording prior values for rollback, see `Registers::with_transaction`
+pub struct RegisterTransaction<'a, T: Registers<Word>, Word: Copy, const N: usize> {
+    regs: &'a mut T,
+    undo: [Option<(Word, RestoreFn<T, Word>)>; N],
+    count: usize,
+}
+
+impl<'a, T: Registers<Word>, Word: Copy, const N: usize> RegisterTransaction<'a, T, Word, N> {
+    /// Read a register value
+    pub fn read<R: Register<Word = Word>>(&mut self) -> Result<R, TransactionError<T::Error>> {
+        Ok(self.regs.read_register()?)
+    }
+
+    /// Write a register value, recording the prior value for rollback
+    pub fn write<R: Register<Word = Word>>(
+        &mut self,
+        value: R,
+    ) -> Result<(), TransactionError<T::Error>> {
+        if self.count >= N {
+            return Err(TransactionError::Full);
+        }
+
+        let prior: R = self.regs.read_register()?;
+        self.undo[self.count] = Some((prior.into(), Self::restore::<R>));
+        self.count += 1;
+
+        self.regs.write_register(value)?;
+
+        Ok(())
+    }
+
+    fn restore<R: Register<Word = Word>>(
+        regs: &mut T,
+        w: Word,
+    ) -> Result<(), TransactionError<T::Error>> {
+        let r = R::try_from(w).map_err(|_| TransactionError::RollbackInvalid)?;
+        regs.write_register(r)
+            .map_err(TransactionError::RollbackFailed)
+    }
+
+    // Restore all recorded values, continuing past failures and returning the first error
+    fn rollback(&mut self) -> Result<(), TransactionError<T::Error>> {
+        let mut res = Ok(());
+
+        while self.count > 0 {
+            self.count -= 1;
+
+            if let Some((w, restore)) = self.undo[self.count].take() {
+                let r = restore(self.regs, w);
+                if res.is_ok() {
+                    res = r;
+                }
+            }
+        }
+
+        res
+    }
 }
 
 #[cfg(feature = "std")]
@@ -261,9 +974,10 @@ fn duration_from_str(s: &str) -> Result<core::time::Duration, humantime::Duratio
 
 #[cfg(test)]
 mod tests {
-    use crate::{Register, Registers};
+    use crate::config::{SyncWords, MAX_SYNC_WORDS, MAX_SYNC_WORD_LEN};
+    use crate::{RawRegisters, Register, Registers, TransactionError};
 
-    use core::convert::{Infallible, TryInto};
+    use core::convert::{Infallible, TryFrom, TryInto};
 
     #[derive(Clone, Copy, Debug, PartialEq)]
     struct TestRegister1 {
@@ -311,10 +1025,49 @@ mod tests {
         const ADDRESS: u8 = 1;
     }
 
+    // Register whose stored word does not convert back, for rollback failures
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct TestRegister3 {
+        value: u8,
+    }
+
+    impl TryFrom<u8> for TestRegister3 {
+        type Error = ();
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                0..=0x7f => Ok(Self { value }),
+                _ => Err(()),
+            }
+        }
+    }
+
+    impl From<TestRegister3> for u8 {
+        fn from(reg: TestRegister3) -> Self {
+            reg.value | 0x80
+        }
+    }
+
+    impl Register for TestRegister3 {
+        type Word = u8;
+        type Error = ();
+        const ADDRESS: u8 = 2;
+    }
+
     struct TestDevice {
         device_register: [u8; 3],
     }
 
+    impl RawRegisters for TestDevice {
+        type Error = ();
+        fn read_raw(&mut self, address: u8) -> Result<u8, Self::Error> {
+            self.device_register
+                .get(address as usize)
+                .copied()
+                .ok_or(())
+        }
+    }
+
     impl Registers<u8> for TestDevice {
         type Error = ();
         fn read_register<R: Register<Word = u8>>(&mut self) -> Result<R, Self::Error> {
@@ -348,6 +1101,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sync_words() {
+        let w = SyncWords::from_slices(&[&[0x12, 0x34][..], &[0x56]]).unwrap();
+        assert_eq!(w.len(), 2);
+
+        let mut i = w.iter();
+        assert_eq!(i.next(), Some(&[0x12, 0x34][..]));
+        assert_eq!(i.next(), Some(&[0x56][..]));
+        assert_eq!(i.next(), None);
+
+        assert!(SyncWords::from_slices(&[&[0u8; MAX_SYNC_WORD_LEN + 1]]).is_none());
+        assert!(SyncWords::from_slices(&[&[0u8][..]; MAX_SYNC_WORDS + 1]).is_none());
+    }
+
     #[test]
     fn update_register1() {
         let mut device = TestDevice {
@@ -381,4 +1148,127 @@ mod tests {
             TestRegister2 { value: [2, 3] }
         );
     }
+
+    #[test]
+    fn standard_state() {
+        use crate::{RadioState, StandardState};
+
+        assert_eq!(StandardState::idle(), StandardState::Idle);
+        assert_eq!(StandardState::sleep(), StandardState::Sleep);
+
+        assert!(StandardState::Rx.is_rx());
+        assert!(!StandardState::Rx.is_tx());
+        assert!(StandardState::Tx.is_tx());
+        assert!(!StandardState::Cad.is_rx());
+    }
+
+    #[test]
+    fn register_dump() {
+        let mut device = TestDevice {
+            device_register: [1, 2, 3],
+        };
+
+        let mut out = [0u8; 3];
+        assert_eq!(device.dump(1, 2, &mut out), Ok(2));
+        assert_eq!(out, [2, 3, 0]);
+
+        assert_eq!(device.dump(2, 2, &mut out), Err(()));
+
+        // Dumps are limited to the output buffer length
+        let mut out = [0u8; 2];
+        assert_eq!(device.dump(0, 3, &mut out), Ok(2));
+        assert_eq!(out, [1, 2]);
+    }
+
+    #[test]
+    fn rssi_scale_to_dbm() {
+        use crate::RssiScale;
+
+        assert_eq!(RssiScale::Dbm.to_dbm(-80), -80);
+
+        // Half dB steps with a -164 dBm offset
+        let s = RssiScale::Scaled {
+            step_cdb: 50,
+            offset_dbm: -164,
+        };
+        assert_eq!(s.to_dbm(100), -114);
+    }
+
+    #[test]
+    fn transmit_mtu() {
+        use crate::blocking::{BlockingError, BlockingOptions, BlockingTransmit};
+        use crate::Transmit;
+
+        struct TestRadio;
+
+        impl Transmit for TestRadio {
+            type Error = Infallible;
+            const MTU: Option<usize> = Some(4);
+
+            fn start_transmit(&mut self, _data: &[u8]) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+                Ok(true)
+            }
+        }
+
+        impl embedded_hal::delay::DelayNs for TestRadio {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        let mut radio = TestRadio;
+
+        assert_eq!(
+            radio.do_transmit(&[0u8; 4], BlockingOptions::default()),
+            Ok(())
+        );
+        assert_eq!(
+            radio.do_transmit(&[0u8; 5], BlockingOptions::default()),
+            Err(BlockingError::PayloadTooLarge)
+        );
+    }
+
+    #[test]
+    fn register_transaction_rollback() {
+        let mut device = TestDevice {
+            device_register: [1, 2, 3],
+        };
+
+        // Successful transactions apply all writes
+        Registers::<u8>::with_transaction::<2, _>(&mut device, |t| {
+            t.write(TestRegister1 { value: 5 })?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(device.device_register, [5, 2, 3]);
+
+        // Failed transactions restore prior values
+        let res = Registers::<[u8; 2]>::with_transaction::<2, _>(&mut device, |t| {
+            t.write(TestRegister2 { value: [7, 8] })?;
+            Err(TransactionError::Inner(()))
+        });
+        assert_eq!(res, Err(TransactionError::Inner(())));
+        assert_eq!(device.device_register, [5, 2, 3]);
+
+        // Exceeding the undo log capacity fails and rolls back
+        let res = Registers::<u8>::with_transaction::<1, _>(&mut device, |t| {
+            t.write(TestRegister1 { value: 6 })?;
+            t.write(TestRegister1 { value: 7 })?;
+            Ok(())
+        });
+        assert_eq!(res, Err(TransactionError::Full));
+        assert_eq!(device.device_register, [5, 2, 3]);
+
+        // Prior values that cannot be converted back report a failed rollback,
+        // with remaining registers still restored
+        let res = Registers::<u8>::with_transaction::<2, _>(&mut device, |t| {
+            t.write(TestRegister1 { value: 9 })?;
+            t.write(TestRegister3 { value: 4 })?;
+            Err(TransactionError::Inner(()))
+        });
+        assert_eq!(res, Err(TransactionError::RollbackInvalid));
+        assert_eq!(device.device_register[0], 5);
+    }
 }
@@ -0,0 +1,307 @@
+//! Link test helpers shared by blocking and async ping-pong implementations
+//!
+//! These are `no_std` compatible for use on-device.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+use core::convert::TryInto;
+use core::time::Duration;
+
+#[cfg(not(feature = "defmt"))]
+use log::debug;
+
+#[cfg(feature = "defmt")]
+use defmt::debug;
+
+#[cfg(feature = "clap")]
+use clap::Parser;
+
+use crate::blocking::BlockingOptions;
+use crate::stats::RunningStats;
+use crate::ReceiveInfo;
+
+/// Configuration for Echo operation
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+pub struct PingPongOptions {
+    /// Specify the number of rounds to tx/rx
+    #[cfg_attr(feature = "clap", clap(long, default_value = "100"))]
+    pub rounds: u32,
+
+    /// Power in dBm (range -18dBm to 13dBm)
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub power: Option<i8>,
+
+    /// Specify delay for response message
+    #[cfg_attr(feature="clap", clap(long, default_value="100ms", value_parser=crate::duration_from_str))]
+    pub delay: Duration,
+
+    /// Parse RSSI and other info from response messages
+    /// (echo server must have --append-info set)
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub parse_info: bool,
+
+    /// Format of receive info appended by the echo server
+    #[cfg_attr(feature = "clap", clap(long, value_enum, default_value = "rssi"))]
+    pub info_format: InfoFormat,
+
+    #[cfg_attr(feature = "clap", clap(flatten))]
+    pub blocking_options: BlockingOptions,
+}
+
+/// Wire format for receive info appended to echoed messages
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum InfoFormat {
+    /// RSSI only, as a 2 byte big endian `i16` (compatibility format)
+    Rssi,
+    /// Flags byte followed by each available field
+    ///
+    /// Flags bits are `0x01` RSSI (`i16`), `0x02` LQI (`u16`), `0x04` SNR (`i16`)
+    /// and `0x08` timestamp (`u64`), with fields following in that order as big endian values.
+    Extended,
+}
+
+/// Receive info exchanged between echo and ping-pong peers
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct RemoteInfo {
+    pub rssi: Option<i16>,
+    pub lqi: Option<u16>,
+    pub snr: Option<i16>,
+    pub timestamp: Option<u64>,
+}
+
+const INFO_FLAG_RSSI: u8 = 0x01;
+const INFO_FLAG_LQI: u8 = 0x02;
+const INFO_FLAG_SNR: u8 = 0x04;
+const INFO_FLAG_TIMESTAMP: u8 = 0x08;
+
+impl RemoteInfo {
+    /// Collect the available fields from a `ReceiveInfo` object
+    pub fn from_info<I: ReceiveInfo>(info: &I) -> Self {
+        Self {
+            rssi: Some(info.rssi()),
+            lqi: info.lqi(),
+            snr: info.snr(),
+            timestamp: info.timestamp(),
+        }
+    }
+
+    /// Compute the encoded length of this info in the provided format
+    pub fn encoded_len(&self, format: InfoFormat) -> usize {
+        if format == InfoFormat::Rssi {
+            return 2;
+        }
+
+        1 + self.rssi.map_or(0, |_| 2)
+            + self.lqi.map_or(0, |_| 2)
+            + self.snr.map_or(0, |_| 2)
+            + self.timestamp.map_or(0, |_| 8)
+    }
+
+    /// Encode info into the provided buffer, returning the encoded length
+    ///
+    /// Panics if the buffer is shorter than `encoded_len`.
+    pub fn encode(&self, format: InfoFormat, buff: &mut [u8]) -> usize {
+        if format == InfoFormat::Rssi {
+            buff[..2].copy_from_slice(&self.rssi.unwrap_or(i16::MIN).to_be_bytes());
+            return 2;
+        }
+
+        let mut flags = 0;
+        let mut n = 1;
+
+        if let Some(v) = self.rssi {
+            buff[n..n + 2].copy_from_slice(&v.to_be_bytes());
+            flags |= INFO_FLAG_RSSI;
+            n += 2;
+        }
+        if let Some(v) = self.lqi {
+            buff[n..n + 2].copy_from_slice(&v.to_be_bytes());
+            flags |= INFO_FLAG_LQI;
+            n += 2;
+        }
+        if let Some(v) = self.snr {
+            buff[n..n + 2].copy_from_slice(&v.to_be_bytes());
+            flags |= INFO_FLAG_SNR;
+            n += 2;
+        }
+        if let Some(v) = self.timestamp {
+            buff[n..n + 8].copy_from_slice(&v.to_be_bytes());
+            flags |= INFO_FLAG_TIMESTAMP;
+            n += 8;
+        }
+
+        buff[0] = flags;
+        n
+    }
+
+    /// Decode info from the provided buffer, returning None if the buffer is too short
+    pub fn decode(format: InfoFormat, data: &[u8]) -> Option<Self> {
+        if format == InfoFormat::Rssi {
+            return match data.len() >= 2 {
+                true => Some(Self {
+                    rssi: Some(i16::from_be_bytes([data[0], data[1]])),
+                    ..Default::default()
+                }),
+                false => None,
+            };
+        }
+
+        let flags = *data.first()?;
+        let mut info = Self::default();
+        let mut n = 1;
+
+        if flags & INFO_FLAG_RSSI != 0 {
+            info.rssi = Some(i16::from_be_bytes(data.get(n..n + 2)?.try_into().ok()?));
+            n += 2;
+        }
+        if flags & INFO_FLAG_LQI != 0 {
+            info.lqi = Some(u16::from_be_bytes(data.get(n..n + 2)?.try_into().ok()?));
+            n += 2;
+        }
+        if flags & INFO_FLAG_SNR != 0 {
+            info.snr = Some(i16::from_be_bytes(data.get(n..n + 2)?.try_into().ok()?));
+            n += 2;
+        }
+        if flags & INFO_FLAG_TIMESTAMP != 0 {
+            info.timestamp = Some(u64::from_be_bytes(data.get(n..n + 8)?.try_into().ok()?));
+        }
+
+        Some(info)
+    }
+}
+
+/// Read remote receive info from a ping-pong response frame
+///
+/// Returns None where the frame is too short to contain the index and info,
+/// rather than panicking on truncated or malformed frames.
+pub fn read_info(data: &[u8], format: InfoFormat) -> Option<RemoteInfo> {
+    RemoteInfo::decode(format, data.get(4..)?)
+}
+
+pub struct LinkTestInfo {
+    pub sent: u32,
+    pub received: u32,
+    pub local_rssi: RunningStats,
+    pub remote_rssi: RunningStats,
+}
+
+impl LinkTestInfo {
+    /// Create a new link test info object for the specified number of rounds
+    pub fn new(rounds: u32) -> Self {
+        Self {
+            sent: rounds,
+            received: 0,
+            local_rssi: RunningStats::new(),
+            remote_rssi: RunningStats::new(),
+        }
+    }
+
+    /// Encode a ping message for the provided round, returning the message length
+    pub fn encode_ping(buff: &mut [u8], index: u32) -> usize {
+        buff[..4].copy_from_slice(&index.to_be_bytes());
+        4
+    }
+
+    /// Parse a response for the provided round and update link statistics
+    ///
+    /// Returns false if the response does not match the expected round
+    pub fn update<I: ReceiveInfo>(
+        &mut self,
+        index: u32,
+        data: &[u8],
+        info: &I,
+        parse_info: Option<InfoFormat>,
+    ) -> bool {
+        let receive_index = match data.get(0..4) {
+            Some(d) => u32::from_be_bytes([d[0], d[1], d[2], d[3]]),
+            None => {
+                debug!("Truncated response");
+                return false;
+            }
+        };
+        if receive_index != index {
+            debug!("Invalid receive index");
+            return false;
+        }
+
+        // Parse info if provided
+        let remote_info = parse_info.and_then(|f| read_info(data, f));
+
+        debug!(
+            "Received response {} with local rssi: {} and remote info: {:?}",
+            receive_index,
+            info.rssi(),
+            remote_info
+        );
+
+        self.received += 1;
+        self.local_rssi.update(info.rssi() as f32);
+        if let Some(rssi) = remote_info.and_then(|i| i.rssi) {
+            self.remote_rssi.update(rssi as f32);
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicInfo;
+
+    #[test]
+    fn remote_info_encode_decode() {
+        let info = RemoteInfo::from_info(&BasicInfo::new(-72, 200));
+        let mut buff = [0u8; 32];
+
+        // Compatibility format carries only RSSI
+        let n = info.encode(InfoFormat::Rssi, &mut buff);
+        assert_eq!(n, 2);
+        assert_eq!(info.encoded_len(InfoFormat::Rssi), n);
+        assert_eq!(
+            RemoteInfo::decode(InfoFormat::Rssi, &buff[..n]),
+            Some(RemoteInfo {
+                rssi: Some(-72),
+                ..Default::default()
+            })
+        );
+
+        // Extended format carries all available fields
+        let n = info.encode(InfoFormat::Extended, &mut buff);
+        assert_eq!(n, 5);
+        assert_eq!(info.encoded_len(InfoFormat::Extended), n);
+        assert_eq!(
+            RemoteInfo::decode(InfoFormat::Extended, &buff[..n]),
+            Some(info)
+        );
+
+        assert_eq!(RemoteInfo::decode(InfoFormat::Extended, &buff[..3]), None);
+    }
+
+    #[test]
+    fn link_test_truncated_response() {
+        let info = BasicInfo::new(-60, 0);
+        let mut link = LinkTestInfo::new(2);
+
+        // Truncated index
+        assert!(!link.update(0, &[0x00, 0x00], &info, Some(InfoFormat::Rssi)));
+
+        // Truncated info
+        assert_eq!(
+            read_info(&[0x00, 0x00, 0x00, 0x01, 0xff], InfoFormat::Rssi),
+            None
+        );
+        assert!(link.update(
+            1,
+            &[0x00, 0x00, 0x00, 0x01, 0xff],
+            &info,
+            Some(InfoFormat::Rssi)
+        ));
+
+        assert_eq!(link.received, 1);
+        assert_eq!(link.remote_rssi.count, 0);
+    }
+}
@@ -7,6 +7,7 @@
 //! ## Copyright 2020-2022 Ryan Kurte
 
 extern crate std;
+use core::time::Duration;
 use std::convert::Infallible;
 use std::fmt::Debug;
 use std::vec::Vec;
@@ -18,8 +19,11 @@ use embedded_hal::delay::DelayNs;
 use embedded_hal_mock::common::Generic;
 
 use crate::{
-    BasicInfo, Busy, Channel, Interrupts, Power, RadioState, Receive, ReceiveInfo, Rssi, State,
-    Transmit,
+    config::{ConfigError, ConfigOption, Configure, PacketConfig},
+    BasicInfo, Busy, Cad, Channel, DetectionTiming, DeviceStatus, DeviceStatusFlags, Diagnostics,
+    FhssEngine, Fifo, Interrupts, IrqFlags, Nvm, Power, PreambleDetect, RadioState, RawRegisters,
+    Receive, ReceiveInfo, Rssi, RssiScale, Sampler, ScheduledTransmit, Shutdown, State, Transmit,
+    TxPowerMonitor,
 };
 
 /// Generic mock radio
@@ -35,6 +39,38 @@ pub struct Radio<
     E: Debug + Clone + PartialEq,
 > {
     inner: Generic<Transaction<St, Reg, Ch, Inf, Irq, E>>,
+    permissive_delay: bool,
+    clock: MockClock,
+    power_range: Option<(i8, i8)>,
+    frequency_range: Option<(u32, u32)>,
+    rssi_scale: RssiScale,
+}
+
+/// Simulated clock for mock radios, advanced by `DelayNs` calls
+///
+/// Clones share the same underlying time, so a handle fetched with `Radio::clock`
+/// observes delays performed by the radio without real sleeping.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    now_ns: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl MockClock {
+    /// Create a new clock starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the current simulated time since clock creation
+    pub fn now(&self) -> Duration {
+        Duration::from_nanos(self.now_ns.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    /// Advance the simulated time
+    pub fn advance(&self, ns: u64) {
+        self.now_ns
+            .fetch_add(ns, std::sync::atomic::Ordering::SeqCst);
+    }
 }
 
 impl<St, Reg, Ch, Inf, Irq, E> Radio<St, Reg, Ch, Inf, Irq, E>
@@ -49,7 +85,61 @@ where
     pub fn new(expectations: &[Transaction<St, Reg, Ch, Inf, Irq, E>]) -> Self {
         let inner = Generic::new(expectations);
 
-        Self { inner }
+        Self {
+            inner,
+            permissive_delay: false,
+            clock: MockClock::new(),
+            power_range: None,
+            frequency_range: None,
+            rssi_scale: RssiScale::Dbm,
+        }
+    }
+
+    /// Create a mock radio expecting a transmit of `tx` followed by receipt of `rx`
+    ///
+    /// This builds the transaction sequence for a `BlockingTransmit::do_transmit` and
+    /// `BlockingReceive::do_receive` round-trip where each operation completes on the first check.
+    pub fn transmit_then_receive(tx: &[u8], rx: &[u8], info: Inf) -> Self {
+        Self::new(&[
+            Transaction::start_transmit(tx.to_vec(), None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((rx.to_vec(), info))),
+        ])
+    }
+
+    /// Set the RSSI scale reported by `Rssi::rssi_scale`
+    pub fn set_rssi_scale(&mut self, scale: RssiScale) {
+        self.rssi_scale = scale;
+    }
+
+    /// Set the power range reported by `Power::power_range`
+    pub fn set_power_range(&mut self, range: Option<(i8, i8)>) {
+        self.power_range = range;
+    }
+
+    /// Set the frequency range reported by `Configure::frequency_range`
+    pub fn set_frequency_range(&mut self, range: Option<(u32, u32)>) {
+        self.frequency_range = range;
+    }
+
+    /// Enable or disable permissive delay mode
+    ///
+    /// In permissive mode `delay_ns` calls are recorded but not matched against expectations,
+    /// use `total_delay_ns` to check the total delay instead.
+    pub fn set_permissive_delay(&mut self, permissive: bool) {
+        self.permissive_delay = permissive;
+    }
+
+    /// Fetch the total delay in nanoseconds requested via `DelayNs`
+    pub fn total_delay_ns(&self) -> u64 {
+        self.clock.now().as_nanos() as u64
+    }
+
+    /// Fetch a handle to the simulated clock advanced by `DelayNs` calls
+    pub fn clock(&self) -> MockClock {
+        self.clock.clone()
     }
 
     pub fn expect(&mut self, expectations: &[Transaction<St, Reg, Ch, Inf, Irq, E>]) {
@@ -68,8 +158,82 @@ where
 /// Concrete mock radio using mock types
 pub type MockRadio = Radio<MockState, u8, u8, BasicInfo, u8, MockError>;
 
+/// Concrete mock radio using named interrupt flags (`BasicIrq`)
+pub type MockIrqRadio = Radio<MockState, u8, u8, BasicInfo, BasicIrq, MockError>;
+
+/// BasicIrq interrupt flags for use with mock radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BasicIrq(u16);
+
+impl BasicIrq {
+    pub const TX_DONE: Self = Self(1 << 0);
+    pub const RX_DONE: Self = Self(1 << 1);
+    pub const CRC_ERROR: Self = Self(1 << 2);
+    pub const TIMEOUT: Self = Self(1 << 3);
+    pub const PREAMBLE_DETECTED: Self = Self(1 << 4);
+    pub const CAD_DONE: Self = Self(1 << 5);
+    pub const CAD_DETECTED: Self = Self(1 << 6);
+
+    /// Create an empty flag set
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Create a flag set from raw bits
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Fetch raw flag bits
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Check whether no flags are set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check whether all of the provided flags are set
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for BasicIrq {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for BasicIrq {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl IrqFlags for BasicIrq {
+    fn tx_done(&self) -> bool {
+        self.contains(Self::TX_DONE)
+    }
+
+    fn rx_done(&self) -> bool {
+        self.contains(Self::RX_DONE)
+    }
+
+    fn crc_error(&self) -> bool {
+        self.contains(Self::CRC_ERROR)
+    }
+
+    fn timeout(&self) -> bool {
+        self.contains(Self::TIMEOUT)
+    }
+}
+
 /// MockState for use with mock radio
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MockState {
     Idle,
     Sleep,
@@ -78,6 +242,19 @@ pub enum MockState {
     Transmitting,
 }
 
+impl core::fmt::Display for MockState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            MockState::Idle => "idle",
+            MockState::Sleep => "sleep",
+            MockState::Receive => "receive",
+            MockState::Receiving => "receiving",
+            MockState::Transmitting => "transmitting",
+        };
+        f.write_str(s)
+    }
+}
+
 impl crate::RadioState for MockState {
     fn idle() -> Self {
         Self::Idle
@@ -86,6 +263,14 @@ impl crate::RadioState for MockState {
     fn sleep() -> Self {
         Self::Sleep
     }
+
+    fn is_rx(&self) -> bool {
+        matches!(self, Self::Receive | Self::Receiving)
+    }
+
+    fn is_tx(&self) -> bool {
+        matches!(self, Self::Transmitting)
+    }
 }
 
 /// MockError for use with mock radio
@@ -96,6 +281,16 @@ pub enum MockError {
     Timeout,
 }
 
+// Display is derived by thiserror where enabled
+#[cfg(not(feature = "thiserror"))]
+impl core::fmt::Display for MockError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MockError::Timeout => f.write_str("Timeout"),
+        }
+    }
+}
+
 /// Transactions describe interactions with a radio device
 #[derive(Debug, Clone, PartialEq)]
 pub struct Transaction<St, Reg, Ch, Inf, Irq, E> {
@@ -120,6 +315,22 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Shutdown the radio
+    pub fn shutdown(err: Option<E>) -> Self {
+        Self {
+            request: Request::Shutdown,
+            response: err.into(),
+        }
+    }
+
+    /// Wake the radio from shutdown
+    pub fn wakeup(err: Option<E>) -> Self {
+        Self {
+            request: Request::Wakeup,
+            response: err.into(),
+        }
+    }
+
     /// Check whether radio is currently busy
     pub fn is_busy(res: Result<bool, E>) -> Self {
         Self {
@@ -128,6 +339,62 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Start channel activity detection
+    pub fn start_cad(err: Option<E>) -> Self {
+        Self {
+            request: Request::StartCad,
+            response: err.into(),
+        }
+    }
+
+    /// Check for channel activity detection completion
+    pub fn check_cad(res: Result<Option<bool>, E>) -> Self {
+        Self {
+            request: Request::CheckCad,
+            response: res.map_or_else(Response::Err, Response::MaybeBool),
+        }
+    }
+
+    /// Check for preamble detection
+    pub fn preamble_detected(res: Result<bool, E>) -> Self {
+        Self {
+            request: Request::PreambleDetected,
+            response: res.map_or_else(Response::Err, Response::Bool),
+        }
+    }
+
+    /// Fetch device status flags
+    pub fn get_status(res: Result<DeviceStatusFlags, E>) -> Self {
+        Self {
+            request: Request::GetStatus,
+            response: res.map_or_else(Response::Err, Response::Status),
+        }
+    }
+
+    /// Clear device status flags
+    pub fn clear_status(flags: DeviceStatusFlags, err: Option<E>) -> Self {
+        Self {
+            request: Request::ClearStatus(flags),
+            response: err.into(),
+        }
+    }
+
+    /// Fetch preamble and sync detection timing
+    pub fn detection_timing(res: Result<Option<DetectionTiming>, E>) -> Self {
+        Self {
+            request: Request::DetectionTiming,
+            response: res.map_or_else(Response::Err, Response::Timing),
+        }
+    }
+
+    /// Fetch FIFO levels
+    pub fn fifo_level(res: Result<(usize, usize), E>) -> Self {
+        Self {
+            request: Request::FifoLevel,
+            response: res.map_or_else(Response::Err, |(rx, tx)| Response::FifoLevel(rx, tx)),
+        }
+    }
+
     /// Set a radio register
     pub fn set_register(reg: Reg, value: u8, err: Option<E>) -> Self {
         Self {
@@ -144,6 +411,38 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Read a radio register by address
+    pub fn read_raw(addr: u8, res: Result<u8, E>) -> Self {
+        Self {
+            request: Request::ReadRaw(addr),
+            response: res.map_or_else(Response::Err, Response::Register),
+        }
+    }
+
+    /// Burst read radio registers by address
+    pub fn read_burst(start: u8, res: Result<Vec<u8>, E>) -> Self {
+        Self {
+            request: Request::ReadBurst(start),
+            response: res.map_or_else(Response::Err, Response::Data),
+        }
+    }
+
+    /// Read from non-volatile memory
+    pub fn read_nvm(addr: u16, res: Result<Vec<u8>, E>) -> Self {
+        Self {
+            request: Request::ReadNvm(addr),
+            response: res.map_or_else(Response::Err, Response::Data),
+        }
+    }
+
+    /// Write to non-volatile memory
+    pub fn write_nvm(addr: u16, data: Vec<u8>, err: Option<E>) -> Self {
+        Self {
+            request: Request::WriteNvm(addr, data),
+            response: err.into(),
+        }
+    }
+
     /// Set a radio channel
     pub fn set_channel(ch: Ch, err: Option<E>) -> Self {
         Self {
@@ -152,6 +451,38 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Set the radio channel using a fast retune
+    pub fn set_channel_fast(ch: Ch, err: Option<E>) -> Self {
+        Self {
+            request: Request::SetChannelFast(ch),
+            response: err.into(),
+        }
+    }
+
+    /// Configure hardware frequency hopping
+    pub fn configure_fhss(plan: Vec<Ch>, dwell: Duration, seed: u32, err: Option<E>) -> Self {
+        Self {
+            request: Request::ConfigureFhss(plan, dwell, seed),
+            response: err.into(),
+        }
+    }
+
+    /// Start hardware frequency hopping
+    pub fn start_fhss(err: Option<E>) -> Self {
+        Self {
+            request: Request::StartFhss,
+            response: err.into(),
+        }
+    }
+
+    /// Stop hardware frequency hopping
+    pub fn stop_fhss(err: Option<E>) -> Self {
+        Self {
+            request: Request::StopFhss,
+            response: err.into(),
+        }
+    }
+
     /// Set radio power
     pub fn set_power(power: i8, err: Option<E>) -> Self {
         Self {
@@ -160,6 +491,30 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Set a configuration option
+    pub fn set_option(o: ConfigOption, err: Option<ConfigError<E>>) -> Self {
+        Self {
+            request: Request::SetOption(o),
+            response: err.map_or(Response::Ok, Response::ConfigErr),
+        }
+    }
+
+    /// Get a configuration option, matched by option variant
+    pub fn get_option(o: ConfigOption, res: Result<ConfigOption, ConfigError<E>>) -> Self {
+        Self {
+            request: Request::GetOption(o),
+            response: res.map_or_else(Response::ConfigErr, Response::Option),
+        }
+    }
+
+    /// Fetch the effective MTU
+    pub fn effective_mtu(res: Result<u16, ConfigError<E>>) -> Self {
+        Self {
+            request: Request::EffectiveMtu,
+            response: res.map_or_else(Response::ConfigErr, Response::Mtu),
+        }
+    }
+
     /// Start radio transmission
     pub fn start_transmit(data: Vec<u8>, err: Option<E>) -> Self {
         Self {
@@ -168,6 +523,30 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Estimate remaining transmit time
+    pub fn transmit_remaining(res: Result<Option<Duration>, E>) -> Self {
+        Self {
+            request: Request::TransmitRemaining,
+            response: res.map_or_else(Response::Err, Response::Remaining),
+        }
+    }
+
+    /// Start an extended preamble transmission
+    pub fn transmit_preamble(duration: Duration, err: Option<ConfigError<E>>) -> Self {
+        Self {
+            request: Request::TransmitPreamble(duration),
+            response: err.map_or(Response::Ok, Response::ConfigErr),
+        }
+    }
+
+    /// Schedule a transmission at the provided time
+    pub fn transmit_at(data: Vec<u8>, when: u64, err: Option<E>) -> Self {
+        Self {
+            request: Request::TransmitAt(data, when),
+            response: err.into(),
+        }
+    }
+
     /// Check for transmission completed
     pub fn check_transmit(res: Result<bool, E>) -> Self {
         Self {
@@ -200,6 +579,38 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Fetch received data, information, and CRC validity
+    pub fn get_received_raw(res: Result<(Vec<u8>, Inf, bool), E>) -> Self {
+        Self {
+            request: Request::GetReceivedRaw,
+            response: res.map_or_else(Response::Err, |(d, i, v)| Response::ReceivedRaw(d, i, v)),
+        }
+    }
+
+    /// Drain all buffered received packets
+    pub fn drain_received(res: Result<Vec<(Vec<u8>, Inf)>, E>) -> Self {
+        Self {
+            request: Request::DrainReceived,
+            response: res.map_or_else(Response::Err, Response::Frames),
+        }
+    }
+
+    /// Check for receive overflow
+    pub fn rx_overflow(res: Result<bool, E>) -> Self {
+        Self {
+            request: Request::RxOverflow,
+            response: res.map_or_else(Response::Err, Response::Bool),
+        }
+    }
+
+    /// Check whether the last received packet was a broadcast
+    pub fn last_rx_was_broadcast(res: Result<Option<bool>, E>) -> Self {
+        Self {
+            request: Request::LastRxWasBroadcast,
+            response: res.map_or_else(Response::Err, Response::MaybeBool),
+        }
+    }
+
     /// Fetch radio IRQs
     pub fn get_irq(clear: bool, res: Result<Irq, E>) -> Self {
         Self {
@@ -216,6 +627,54 @@ impl<St, Reg, Ch, Inf, Irq, E> Transaction<St, Reg, Ch, Inf, Irq, E> {
         }
     }
 
+    /// Capture a programmed array of RSSI samples
+    pub fn capture_rssi(len: usize, interval: Duration, res: Result<Vec<i16>, E>) -> Self {
+        Self {
+            request: Request::CaptureRssi(len, interval),
+            response: res.map_or_else(Response::Err, Response::Samples),
+        }
+    }
+
+    /// Monitor transmit power, returning a programmed array of samples
+    pub fn monitor_tx(len: usize, interval: Duration, res: Result<Vec<i16>, E>) -> Self {
+        Self {
+            request: Request::MonitorTx(len, interval),
+            response: res.map_or_else(Response::Err, Response::Samples),
+        }
+    }
+
+    /// Fetch packet framing configuration
+    pub fn get_packet_config(res: Result<PacketConfig, E>) -> Self {
+        Self {
+            request: Request::GetPacketConfig,
+            response: res.map_or_else(Response::Err, Response::PacketConfig),
+        }
+    }
+
+    /// Fetch receiver sensitivity
+    pub fn sensitivity(res: Result<Option<i16>, E>) -> Self {
+        Self {
+            request: Request::Sensitivity,
+            response: res.map_or_else(Response::Err, Response::MaybeRssi),
+        }
+    }
+
+    /// Poll for latched packet RSSI
+    pub fn poll_rssi_packet(res: Result<Option<i16>, E>) -> Self {
+        Self {
+            request: Request::PollRssiPacket,
+            response: res.map_or_else(Response::Err, Response::MaybeRssi),
+        }
+    }
+
+    /// Poll for RSSI without changing state
+    pub fn poll_rssi_in_state(res: Result<Option<i16>, E>) -> Self {
+        Self {
+            request: Request::PollRssiInState,
+            response: res.map_or_else(Response::Err, Response::MaybeRssi),
+        }
+    }
+
     /// Delay for a certain time
     pub fn delay_ns(ns: u32) -> Self {
         Self {
@@ -230,22 +689,57 @@ enum Request<St, Reg, Ch> {
     SetState(St),
     GetState,
     IsBusy,
+    StartCad,
+    CheckCad,
+    FifoLevel,
+    PreambleDetected,
+    DetectionTiming,
+    GetStatus,
+    ClearStatus(DeviceStatusFlags),
+    Shutdown,
+    Wakeup,
 
     SetRegister(Reg, u8),
     GetRegister,
+    ReadRaw(u8),
+    ReadBurst(u8),
+
+    ReadNvm(u16),
+    WriteNvm(u16, Vec<u8>),
 
     GetIrq(bool),
     PollRssi,
+    PollRssiInState,
+    PollRssiPacket,
+    CaptureRssi(usize, Duration),
+    MonitorTx(usize, Duration),
+    GetPacketConfig,
+    Sensitivity,
 
     SetChannel(Ch),
+    SetChannelFast(Ch),
+    ConfigureFhss(Vec<Ch>, Duration, u32),
+    StartFhss,
+    StopFhss,
     SetPower(i8),
 
+    SetOption(ConfigOption),
+    GetOption(ConfigOption),
+    EffectiveMtu,
+
     StartTransmit(Vec<u8>),
+    TransmitAt(Vec<u8>, u64),
+    TransmitRemaining,
+    TransmitPreamble(Duration),
     CheckTransmit,
 
     StartReceive,
     CheckReceive(bool),
     GetReceived,
+    GetReceivedRaw,
+    DrainReceived,
+    RxOverflow,
+    LastRxWasBroadcast,
 
     DelayNs(u32),
 }
@@ -257,9 +751,23 @@ enum Response<St, Inf, Irq, E> {
     Register(u8),
     Irq(Irq),
     Rssi(i16),
+    MaybeRssi(Option<i16>),
+    Samples(Vec<i16>),
+    PacketConfig(PacketConfig),
     Received(Vec<u8>, Inf),
+    ReceivedRaw(Vec<u8>, Inf, bool),
+    Frames(Vec<(Vec<u8>, Inf)>),
+    Remaining(Option<Duration>),
+    Data(Vec<u8>),
     Bool(bool),
+    MaybeBool(Option<bool>),
+    FifoLevel(usize, usize),
+    Timing(Option<DetectionTiming>),
+    Status(DeviceStatusFlags),
+    Option(ConfigOption),
+    Mtu(u16),
     Err(E),
+    ConfigErr(ConfigError<E>),
 }
 
 impl<St, Inf, Irq, E> From<Option<E>> for Response<St, Inf, Irq, E> {
@@ -281,6 +789,13 @@ where
     E: PartialEq + Debug + Clone,
 {
     fn delay_ns(&mut self, ns: u32) {
+        self.clock.advance(ns as u64);
+
+        if self.permissive_delay {
+            debug!("Delay {} ns", ns);
+            return;
+        }
+
         let n = self.next().expect("no expectation for delay_ns call");
 
         assert_eq!(&n.request, &Request::DelayNs(ns));
@@ -336,7 +851,7 @@ where
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Busy for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> Shutdown for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
@@ -347,53 +862,44 @@ where
 {
     type Error = E;
 
-    fn is_busy(&mut self) -> Result<bool, Self::Error> {
-        let n = self.next().expect("no expectation for is_busy call");
+    fn shutdown(&mut self) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Shutdown::shutdown call");
 
-        assert_eq!(&n.request, &Request::IsBusy);
+        assert_eq!(&n.request, &Request::Shutdown);
 
         let res = match &n.response {
+            Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
-            Response::Bool(s) => Ok(s.clone()),
             _ => unreachable!(),
         };
 
-        debug!("Is busy {:?}", res);
+        debug!("Shutdown {:?}", res);
 
         res
     }
-}
-
-impl<St, Reg, Ch, Inf, Irq, E> Channel for Radio<St, Reg, Ch, Inf, Irq, E>
-where
-    St: PartialEq + Debug + Clone,
-    Reg: PartialEq + Debug + Clone,
-    Ch: PartialEq + Debug + Clone,
-    Inf: PartialEq + Debug + Clone,
-    Irq: PartialEq + Debug + Clone,
-    E: PartialEq + Debug + Clone,
-{
-    type Channel = Ch;
-    type Error = E;
-
-    fn set_channel(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
-        debug!("Set channel {:?}", channel);
 
+    fn wakeup(&mut self) -> Result<(), Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for State::set_channel call");
+            .expect("no expectation for Shutdown::wakeup call");
 
-        assert_eq!(&n.request, &Request::SetChannel(channel.clone()));
+        assert_eq!(&n.request, &Request::Wakeup);
 
-        match &n.response {
+        let res = match &n.response {
             Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
             _ => unreachable!(),
-        }
+        };
+
+        debug!("Wakeup {:?}", res);
+
+        res
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Power for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> Busy for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
@@ -404,24 +910,24 @@ where
 {
     type Error = E;
 
-    fn set_power(&mut self, power: i8) -> Result<(), Self::Error> {
-        debug!("Set power {:?}", power);
-
-        let n = self
-            .next()
-            .expect("no expectation for Power::set_power call");
+    fn is_busy(&mut self) -> Result<bool, Self::Error> {
+        let n = self.next().expect("no expectation for is_busy call");
 
-        assert_eq!(&n.request, &Request::SetPower(power));
+        assert_eq!(&n.request, &Request::IsBusy);
 
-        match &n.response {
-            Response::Ok => Ok(()),
+        let res = match &n.response {
             Response::Err(e) => Err(e.clone()),
+            Response::Bool(s) => Ok(s.clone()),
             _ => unreachable!(),
-        }
+        };
+
+        debug!("Is busy {:?}", res);
+
+        res
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Rssi for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> Cad for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
@@ -432,26 +938,40 @@ where
 {
     type Error = E;
 
-    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
-        let n = self
-            .next()
-            .expect("no expectation for Rssi::poll_rssi call");
+    fn start_cad(&mut self) -> Result<(), Self::Error> {
+        let n = self.next().expect("no expectation for Cad::start_cad call");
 
-        assert_eq!(&n.request, &Request::PollRssi);
+        assert_eq!(&n.request, &Request::StartCad);
 
         let res = match &n.response {
-            Response::Rssi(v) => Ok(v.clone()),
+            Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
             _ => unreachable!(),
         };
 
-        debug!("Poll RSSI {:?}", res);
+        debug!("Start CAD {:?}", res);
+
+        res
+    }
+
+    fn check_cad(&mut self) -> Result<Option<bool>, Self::Error> {
+        let n = self.next().expect("no expectation for Cad::check_cad call");
+
+        assert_eq!(&n.request, &Request::CheckCad);
+
+        let res = match &n.response {
+            Response::Err(e) => Err(e.clone()),
+            Response::MaybeBool(v) => Ok(*v),
+            _ => unreachable!(),
+        };
+
+        debug!("Check CAD {:?}", res);
 
         res
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Interrupts for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> Fifo for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
@@ -461,28 +981,27 @@ where
     E: PartialEq + Debug + Clone,
 {
     type Error = E;
-    type Irq = Irq;
 
-    fn get_interrupts(&mut self, clear: bool) -> Result<Self::Irq, Self::Error> {
+    fn fifo_level(&mut self) -> Result<(usize, usize), Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Transmit::check_transmit call");
+            .expect("no expectation for Fifo::fifo_level call");
 
-        assert_eq!(&n.request, &Request::GetIrq(clear));
+        assert_eq!(&n.request, &Request::FifoLevel);
 
         let res = match &n.response {
-            Response::Irq(v) => Ok(v.clone()),
             Response::Err(e) => Err(e.clone()),
+            Response::FifoLevel(rx, tx) => Ok((*rx, *tx)),
             _ => unreachable!(),
         };
 
-        debug!("Get Interrupts {:?}", res);
+        debug!("FIFO level {:?}", res);
 
         res
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Transmit for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> PreambleDetect for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
@@ -493,232 +1012,3122 @@ where
 {
     type Error = E;
 
-    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+    fn preamble_detected(&mut self) -> Result<bool, Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Transmit::start_transmit call");
+            .expect("no expectation for PreambleDetect::preamble_detected call");
 
-        assert_eq!(&n.request, &Request::StartTransmit(data.to_vec()));
+        assert_eq!(&n.request, &Request::PreambleDetected);
 
         let res = match &n.response {
-            Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
+            Response::Bool(v) => Ok(*v),
             _ => unreachable!(),
         };
 
-        debug!("Start transmit {:?}: {:?}", data, res);
+        debug!("Preamble detected {:?}", res);
 
         res
     }
 
-    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+    fn detection_timing(&mut self) -> Result<Option<DetectionTiming>, Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Transmit::check_transmit call");
+            .expect("no expectation for PreambleDetect::detection_timing call");
 
-        assert_eq!(&n.request, &Request::CheckTransmit);
+        assert_eq!(&n.request, &Request::DetectionTiming);
 
         let res = match &n.response {
-            Response::Bool(v) => Ok(*v),
             Response::Err(e) => Err(e.clone()),
+            Response::Timing(v) => Ok(*v),
             _ => unreachable!(),
         };
 
-        debug!("Check transmit {:?}", res);
+        debug!("Detection timing {:?}", res);
 
         res
     }
 }
 
-impl<St, Reg, Ch, Inf, Irq, E> Receive for Radio<St, Reg, Ch, Inf, Irq, E>
+impl<St, Reg, Ch, Inf, Irq, E> DeviceStatus for Radio<St, Reg, Ch, Inf, Irq, E>
 where
     St: PartialEq + Debug + Clone,
     Reg: PartialEq + Debug + Clone,
     Ch: PartialEq + Debug + Clone,
-    Inf: ReceiveInfo + PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
     Irq: PartialEq + Debug + Clone,
     E: PartialEq + Debug + Clone,
 {
-    type Info = Inf;
     type Error = E;
 
-    fn start_receive(&mut self) -> Result<(), Self::Error> {
+    fn get_status(&mut self) -> Result<DeviceStatusFlags, Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Receive::start_receive call");
+            .expect("no expectation for DeviceStatus::get_status call");
 
-        assert_eq!(&n.request, &Request::StartReceive);
+        assert_eq!(&n.request, &Request::GetStatus);
 
         let res = match &n.response {
-            Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
+            Response::Status(v) => Ok(*v),
             _ => unreachable!(),
         };
 
-        debug!("Start receive {:?}", res);
+        debug!("Get status {:?}", res);
 
         res
     }
 
-    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+    fn clear_status(&mut self, flags: DeviceStatusFlags) -> Result<(), Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Receive::check_receive call");
+            .expect("no expectation for DeviceStatus::clear_status call");
 
-        assert_eq!(&n.request, &Request::CheckReceive(restart));
+        assert_eq!(&n.request, &Request::ClearStatus(flags));
 
         let res = match &n.response {
-            Response::Bool(v) => Ok(*v),
+            Response::Ok => Ok(()),
             Response::Err(e) => Err(e.clone()),
             _ => unreachable!(),
         };
 
-        debug!("Check receive {:?}", res);
+        debug!("Clear status {:?}", res);
 
         res
     }
+}
 
-    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+impl<St, Reg, Ch, Inf, Irq, E> RawRegisters for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn read_raw(&mut self, address: u8) -> Result<u8, Self::Error> {
         let n = self
             .next()
-            .expect("no expectation for Receive::get_received call");
+            .expect("no expectation for RawRegisters::read_raw call");
 
-        assert_eq!(&n.request, &Request::GetReceived);
+        assert_eq!(&n.request, &Request::ReadRaw(address));
 
         let res = match &n.response {
-            Response::Received(d, i) => {
-                buff[..d.len()].copy_from_slice(&d);
-
-                Ok((d.len(), i.clone()))
-            }
             Response::Err(e) => Err(e.clone()),
+            Response::Register(v) => Ok(*v),
             _ => unreachable!(),
         };
 
-        debug!("Get received {:?}", res);
+        debug!("Read raw 0x{:02x}: {:?}", address, res);
 
         res
     }
-}
 
-#[cfg(test)]
-mod test {
-    use std::vec;
+    fn read_burst(&mut self, start: u8, out: &mut [u8]) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for RawRegisters::read_burst call");
 
-    use super::*;
+        assert_eq!(&n.request, &Request::ReadBurst(start));
 
-    #[test]
-    fn test_radio_mock_set_state() {
-        let mut radio = MockRadio::new(&[Transaction::set_state(MockState::Idle, None)]);
+        let res = match &n.response {
+            Response::Err(e) => Err(e.clone()),
+            Response::Data(d) => {
+                assert_eq!(d.len(), out.len(), "read_burst length mismatch");
+                out.copy_from_slice(d);
+                Ok(())
+            }
+            _ => unreachable!(),
+        };
 
-        radio.set_state(MockState::Idle).unwrap();
+        debug!("Read burst 0x{:02x}: {:?}", start, res);
 
-        radio.done();
+        res
     }
+}
 
-    #[test]
-    #[should_panic]
-    fn test_radio_mock_set_incorrect_state() {
-        let mut radio = MockRadio::new(&[Transaction::set_state(MockState::Idle, None)]);
+impl<St, Reg, Ch, Inf, Irq, E> Nvm for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
 
-        radio.set_state(MockState::Sleep).unwrap();
+    fn read_nvm(&mut self, addr: u16, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let n = self.next().expect("no expectation for Nvm::read_nvm call");
 
-        radio.done();
-    }
+        assert_eq!(&n.request, &Request::ReadNvm(addr));
 
-    #[test]
-    fn test_radio_mock_get_state() {
-        let mut radio = MockRadio::new(&[Transaction::get_state(Ok(MockState::Idle))]);
+        let res = match &n.response {
+            Response::Data(d) => {
+                assert!(
+                    d.len() <= buf.len(),
+                    "read_nvm data ({} bytes) exceeds buffer ({} bytes)",
+                    d.len(),
+                    buf.len()
+                );
+                buf[..d.len()].copy_from_slice(d);
+                Ok(())
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
 
-        let res = radio.get_state().unwrap();
-        assert_eq!(res, MockState::Idle);
+        debug!("Read NVM 0x{:04x}: {:?}", addr, res);
 
-        radio.done();
+        res
     }
 
-    #[test]
-    fn test_radio_mock_set_channel() {
-        let mut radio = MockRadio::new(&[Transaction::set_channel(10, None)]);
-
-        let _res = radio.set_channel(&10).unwrap();
+    fn write_nvm(&mut self, addr: u16, data: &[u8]) -> Result<(), Self::Error> {
+        let n = self.next().expect("no expectation for Nvm::write_nvm call");
 
-        radio.done();
-    }
+        assert_eq!(&n.request, &Request::WriteNvm(addr, data.to_vec()));
 
-    #[test]
-    fn test_radio_mock_set_power() {
-        let mut radio = MockRadio::new(&[Transaction::set_power(10, None)]);
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
 
-        let _res = radio.set_power(10).unwrap();
+        debug!("Write NVM 0x{:04x} {:?}: {:?}", addr, data, res);
 
-        radio.done();
+        res
     }
+}
 
-    #[test]
-    fn test_radio_mock_start_transmit() {
-        let mut radio =
-            MockRadio::new(&[Transaction::start_transmit(vec![0xaa, 0xbb, 0xcc], None)]);
+impl<St, Reg, Ch, Inf, Irq, E> Channel for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Channel = Ch;
+    type Error = E;
 
-        let _res = radio.start_transmit(&[0xaa, 0xbb, 0xcc]).unwrap();
+    fn set_channel(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
+        debug!("Set channel {:?}", channel);
 
-        radio.done();
+        let n = self
+            .next()
+            .expect("no expectation for State::set_channel call");
+
+        assert_eq!(&n.request, &Request::SetChannel(channel.clone()));
+
+        match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        }
     }
 
-    #[test]
-    fn test_radio_mock_check_transmit() {
-        let mut radio = MockRadio::new(&[
-            Transaction::check_transmit(Ok(false)),
-            Transaction::check_transmit(Ok(true)),
-        ]);
+    fn set_channel_fast(&mut self, channel: &Self::Channel) -> Result<(), Self::Error> {
+        debug!("Set channel fast {:?}", channel);
 
-        let res = radio.check_transmit().unwrap();
-        assert_eq!(false, res);
+        let n = self
+            .next()
+            .expect("no expectation for Channel::set_channel_fast call");
 
-        let res = radio.check_transmit().unwrap();
-        assert_eq!(true, res);
+        assert_eq!(&n.request, &Request::SetChannelFast(channel.clone()));
 
-        radio.done();
+        match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        }
     }
+}
 
-    #[test]
-    fn test_radio_mock_start_receive() {
-        let mut radio = MockRadio::new(&[Transaction::start_receive(None)]);
+impl<St, Reg, Ch, Inf, Irq, E> FhssEngine for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Channel = Ch;
+    type Error = E;
 
-        let _res = radio.start_receive().unwrap();
+    fn configure_fhss(
+        &mut self,
+        plan: &[Self::Channel],
+        dwell: Duration,
+        sequence_seed: u32,
+    ) -> Result<(), Self::Error> {
+        debug!("Configure FHSS {:?} (dwell {:?})", plan, dwell);
 
-        radio.done();
+        let n = self
+            .next()
+            .expect("no expectation for FhssEngine::configure_fhss call");
+
+        assert_eq!(
+            &n.request,
+            &Request::ConfigureFhss(plan.to_vec(), dwell, sequence_seed)
+        );
+
+        match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        }
     }
 
-    #[test]
-    fn test_radio_mock_check_receive() {
-        let mut radio = MockRadio::new(&[
-            Transaction::check_receive(true, Ok(false)),
-            Transaction::check_receive(true, Ok(true)),
-        ]);
+    fn start_fhss(&mut self) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for FhssEngine::start_fhss call");
 
-        let res = radio.check_receive(true).unwrap();
-        assert_eq!(false, res);
+        assert_eq!(&n.request, &Request::StartFhss);
 
-        let res = radio.check_receive(true).unwrap();
-        assert_eq!(true, res);
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
 
-        radio.done();
+        debug!("Start FHSS {:?}", res);
+
+        res
     }
 
-    #[test]
-    fn test_radio_mock_get_received() {
-        let mut radio = MockRadio::new(&[Transaction::get_received(Ok((
-            vec![0xaa, 0xbb],
-            BasicInfo::new(10, 12),
+    fn stop_fhss(&mut self) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for FhssEngine::stop_fhss call");
+
+        assert_eq!(&n.request, &Request::StopFhss);
+
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Stop FHSS {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Power for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn set_power(&mut self, power: i8) -> Result<(), Self::Error> {
+        debug!("Set power {:?}", power);
+
+        let n = self
+            .next()
+            .expect("no expectation for Power::set_power call");
+
+        assert_eq!(&n.request, &Request::SetPower(power));
+
+        match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn power_range(&self) -> Option<(i8, i8)> {
+        self.power_range
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Configure for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn set_option(&mut self, o: &ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        debug!("Set option {:?}", o);
+
+        let n = self
+            .next()
+            .expect("no expectation for Configure::set_option call");
+
+        assert_eq!(&n.request, &Request::SetOption(o.clone()));
+
+        match &n.response {
+            Response::Ok => Ok(()),
+            Response::ConfigErr(e) => Err(e.clone()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_option(&mut self, o: &mut ConfigOption) -> Result<(), ConfigError<Self::Error>> {
+        let n = self
+            .next()
+            .expect("no expectation for Configure::get_option call");
+
+        match &n.request {
+            Request::GetOption(e) => assert_eq!(
+                core::mem::discriminant(e),
+                core::mem::discriminant(o),
+                "get_option variant mismatch (expected {:?}, actual {:?})",
+                e,
+                o
+            ),
+            _ => assert_eq!(&n.request, &Request::GetOption(o.clone())),
+        }
+
+        let res = match &n.response {
+            Response::Option(v) => {
+                *o = v.clone();
+                Ok(())
+            }
+            Response::ConfigErr(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Get option {:?}: {:?}", o, res);
+
+        res
+    }
+
+    fn effective_mtu(&mut self) -> Result<u16, ConfigError<Self::Error>> {
+        let n = self
+            .next()
+            .expect("no expectation for Configure::effective_mtu call");
+
+        assert_eq!(&n.request, &Request::EffectiveMtu);
+
+        let res = match &n.response {
+            Response::Mtu(v) => Ok(*v),
+            Response::ConfigErr(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Effective MTU: {:?}", res);
+
+        res
+    }
+
+    fn frequency_range(&self) -> Option<(u32, u32)> {
+        self.frequency_range
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Rssi for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Rssi::poll_rssi call");
+
+        assert_eq!(&n.request, &Request::PollRssi);
+
+        let res = match &n.response {
+            Response::Rssi(v) => Ok(v.clone()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Poll RSSI {:?}", res);
+
+        res
+    }
+
+    fn poll_rssi_in_state(&mut self) -> Result<Option<i16>, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Rssi::poll_rssi_in_state call");
+
+        assert_eq!(&n.request, &Request::PollRssiInState);
+
+        let res = match &n.response {
+            Response::MaybeRssi(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Poll RSSI in state {:?}", res);
+
+        res
+    }
+
+    fn poll_rssi_packet(&mut self) -> Result<Option<i16>, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Rssi::poll_rssi_packet call");
+
+        assert_eq!(&n.request, &Request::PollRssiPacket);
+
+        let res = match &n.response {
+            Response::MaybeRssi(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Poll packet RSSI {:?}", res);
+
+        res
+    }
+
+    fn rssi_scale(&self) -> RssiScale {
+        self.rssi_scale
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Sampler for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    fn capture_rssi(&mut self, samples: &mut [i16], interval: Duration) -> Result<usize, E> {
+        let n = self
+            .next()
+            .expect("no expectation for Sampler::capture_rssi call");
+
+        assert_eq!(&n.request, &Request::CaptureRssi(samples.len(), interval));
+
+        let res = match &n.response {
+            Response::Samples(v) => {
+                let len = v.len().min(samples.len());
+                samples[..len].copy_from_slice(&v[..len]);
+                Ok(len)
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Capture RSSI {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> TxPowerMonitor for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn monitor_tx(&mut self, samples: &mut [i16], interval: Duration) -> Result<usize, E> {
+        let n = self
+            .next()
+            .expect("no expectation for TxPowerMonitor::monitor_tx call");
+
+        assert_eq!(&n.request, &Request::MonitorTx(samples.len(), interval));
+
+        let res = match &n.response {
+            Response::Samples(v) => {
+                let len = v.len().min(samples.len());
+                samples[..len].copy_from_slice(&v[..len]);
+                Ok(len)
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Monitor TX {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Diagnostics for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn get_packet_config(&mut self) -> Result<PacketConfig, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Diagnostics::get_packet_config call");
+
+        assert_eq!(&n.request, &Request::GetPacketConfig);
+
+        let res = match &n.response {
+            Response::PacketConfig(c) => Ok(*c),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Get packet config {:?}", res);
+
+        res
+    }
+
+    fn sensitivity(&mut self) -> Result<Option<i16>, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Diagnostics::sensitivity call");
+
+        assert_eq!(&n.request, &Request::Sensitivity);
+
+        let res = match &n.response {
+            Response::MaybeRssi(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Sensitivity {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Interrupts for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+    type Irq = Irq;
+
+    fn get_interrupts(&mut self, clear: bool) -> Result<Self::Irq, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Transmit::check_transmit call");
+
+        assert_eq!(&n.request, &Request::GetIrq(clear));
+
+        let res = match &n.response {
+            Response::Irq(v) => Ok(v.clone()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Get Interrupts {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Transmit for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Transmit::start_transmit call");
+
+        assert_eq!(&n.request, &Request::StartTransmit(data.to_vec()));
+
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Start transmit {:?}: {:?}", data, res);
+
+        res
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Transmit::check_transmit call");
+
+        assert_eq!(&n.request, &Request::CheckTransmit);
+
+        let res = match &n.response {
+            Response::Bool(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Check transmit {:?}", res);
+
+        res
+    }
+
+    fn transmit_remaining(&mut self) -> Result<Option<Duration>, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Transmit::transmit_remaining call");
+
+        assert_eq!(&n.request, &Request::TransmitRemaining);
+
+        let res = match &n.response {
+            Response::Remaining(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Transmit remaining {:?}", res);
+
+        res
+    }
+
+    fn transmit_preamble(&mut self, duration: Duration) -> Result<(), ConfigError<Self::Error>> {
+        let n = self
+            .next()
+            .expect("no expectation for Transmit::transmit_preamble call");
+
+        assert_eq!(&n.request, &Request::TransmitPreamble(duration));
+
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::ConfigErr(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Transmit preamble {:?}", res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> ScheduledTransmit for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Error = E;
+
+    fn transmit_at(&mut self, data: &[u8], when: u64) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for ScheduledTransmit::transmit_at call");
+
+        assert_eq!(&n.request, &Request::TransmitAt(data.to_vec(), when));
+
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Transmit at {} {:?}: {:?}", when, data, res);
+
+        res
+    }
+}
+
+impl<St, Reg, Ch, Inf, Irq, E> Receive for Radio<St, Reg, Ch, Inf, Irq, E>
+where
+    St: PartialEq + Debug + Clone,
+    Reg: PartialEq + Debug + Clone,
+    Ch: PartialEq + Debug + Clone,
+    Inf: ReceiveInfo + PartialEq + Debug + Clone,
+    Irq: PartialEq + Debug + Clone,
+    E: PartialEq + Debug + Clone,
+{
+    type Info = Inf;
+    type Error = E;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::start_receive call");
+
+        assert_eq!(&n.request, &Request::StartReceive);
+
+        let res = match &n.response {
+            Response::Ok => Ok(()),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Start receive {:?}", res);
+
+        res
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::check_receive call");
+
+        assert_eq!(&n.request, &Request::CheckReceive(restart));
+
+        let res = match &n.response {
+            Response::Bool(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Check receive {:?}", res);
+
+        res
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::get_received call");
+
+        assert_eq!(&n.request, &Request::GetReceived);
+
+        let res = match &n.response {
+            Response::Received(d, i) => {
+                buff[..d.len()].copy_from_slice(&d);
+
+                Ok((d.len(), i.clone()))
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Get received {:?}", res);
+
+        res
+    }
+
+    fn get_received_raw(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<(usize, Self::Info, bool), Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::get_received_raw call");
+
+        assert_eq!(&n.request, &Request::GetReceivedRaw);
+
+        let res = match &n.response {
+            Response::ReceivedRaw(d, i, v) => {
+                buff[..d.len()].copy_from_slice(d);
+
+                Ok((d.len(), i.clone(), *v))
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Get received raw {:?}", res);
+
+        res
+    }
+
+    fn drain_received(
+        &mut self,
+        buff: &mut [u8],
+        mut each: impl FnMut(&[u8], &Self::Info),
+    ) -> Result<usize, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::drain_received call");
+
+        assert_eq!(&n.request, &Request::DrainReceived);
+
+        let res = match &n.response {
+            Response::Frames(f) => {
+                for (d, i) in f {
+                    buff[..d.len()].copy_from_slice(d);
+                    each(&buff[..d.len()], i);
+                }
+                Ok(f.len())
+            }
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Drain received {:?}", res);
+
+        res
+    }
+
+    fn rx_overflow(&mut self) -> Result<bool, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::rx_overflow call");
+
+        assert_eq!(&n.request, &Request::RxOverflow);
+
+        let res = match &n.response {
+            Response::Bool(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Check rx overflow {:?}", res);
+
+        res
+    }
+
+    fn last_rx_was_broadcast(&mut self) -> Result<Option<bool>, Self::Error> {
+        let n = self
+            .next()
+            .expect("no expectation for Receive::last_rx_was_broadcast call");
+
+        assert_eq!(&n.request, &Request::LastRxWasBroadcast);
+
+        let res = match &n.response {
+            Response::MaybeBool(v) => Ok(*v),
+            Response::Err(e) => Err(e.clone()),
+            _ => unreachable!(),
+        };
+
+        debug!("Last rx was broadcast {:?}", res);
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec;
+
+    use super::*;
+    use crate::blocking::{BlockingOptions, BlockingTransmit};
+    use crate::config::{BitOrder, LineCoding, PaKind, SlicerMode, SyncWords, WhiteningMode};
+
+    #[test]
+    fn test_radio_mock_display() {
+        use std::string::ToString;
+
+        assert_eq!(BasicInfo::new(-81, 120).to_string(), "rssi=-81dBm lqi=120");
+        assert_eq!(MockState::Receiving.to_string(), "receiving");
+        assert_eq!(MockError::Timeout.to_string(), "Timeout");
+    }
+
+    #[test]
+    fn test_radio_mock_set_state() {
+        let mut radio = MockRadio::new(&[Transaction::set_state(MockState::Idle, None)]);
+
+        radio.set_state(MockState::Idle).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_radio_mock_set_incorrect_state() {
+        let mut radio = MockRadio::new(&[Transaction::set_state(MockState::Idle, None)]);
+
+        radio.set_state(MockState::Sleep).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_state() {
+        let mut radio = MockRadio::new(&[Transaction::get_state(Ok(MockState::Idle))]);
+
+        let res = radio.get_state().unwrap();
+        assert_eq!(res, MockState::Idle);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_shutdown_wakeup() {
+        let mut radio = MockRadio::new(&[
+            Transaction::shutdown(None),
+            Transaction::wakeup(Some(MockError::Timeout)),
+        ]);
+
+        radio.shutdown().unwrap();
+        assert_eq!(radio.wakeup(), Err(MockError::Timeout));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_nvm() {
+        let mut radio = MockRadio::new(&[
+            Transaction::write_nvm(0x10, vec![0xaa, 0xbb], None),
+            Transaction::read_nvm(0x10, Ok(vec![0xaa, 0xbb])),
+        ]);
+
+        radio.write_nvm(0x10, &[0xaa, 0xbb]).unwrap();
+
+        let mut buff = [0u8; 2];
+        radio.read_nvm(0x10, &mut buff).unwrap();
+        assert_eq!(buff, [0xaa, 0xbb]);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_channel() {
+        let mut radio = MockRadio::new(&[Transaction::set_channel(10, None)]);
+
+        let _res = radio.set_channel(&10).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_channel_fast() {
+        let mut radio = MockRadio::new(&[Transaction::set_channel_fast(11, None)]);
+
+        radio.set_channel_fast(&11).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_fhss() {
+        let dwell = Duration::from_millis(10);
+
+        let mut radio = MockRadio::new(&[
+            Transaction::configure_fhss(vec![1, 2, 3], dwell, 0x1234, None),
+            Transaction::start_fhss(None),
+            Transaction::stop_fhss(None),
+        ]);
+
+        radio.configure_fhss(&[1, 2, 3], dwell, 0x1234).unwrap();
+        radio.start_fhss().unwrap();
+        radio.stop_fhss().unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_software_fhss() {
+        use crate::helpers::SoftwareFhss;
+        use std::time::Instant;
+
+        let dwell = Duration::from_millis(10);
+
+        // Hop sequence for seed 0x1234 over a two channel plan, for two passes
+        const SEQ: [u8; 3] = [1, 2, 2];
+
+        let radio = MockRadio::new(&[
+            Transaction::set_channel_fast(SEQ[0], None),
+            Transaction::set_channel_fast(SEQ[1], None),
+            Transaction::set_channel_fast(SEQ[2], None),
+        ]);
+
+        let mut fhss = SoftwareFhss::new(radio);
+        fhss.configure_fhss(&[1, 2], dwell, 0x1234).unwrap();
+
+        let t = Instant::now();
+
+        // No hopping until started
+        assert!(!fhss.poll(t + dwell).unwrap());
+
+        fhss.start_at(t).unwrap();
+        assert_eq!(fhss.current(), Some(&SEQ[0]));
+
+        // Hop once the dwell time elapses
+        assert!(!fhss.poll(t).unwrap());
+        assert!(fhss.poll(t + dwell).unwrap());
+        assert_eq!(fhss.current(), Some(&SEQ[1]));
+
+        // A late poll hops once, scheduling the next hop from the poll time
+        let late = t + dwell * 5;
+        assert!(fhss.poll(late).unwrap());
+        assert_eq!(fhss.current(), Some(&SEQ[2]));
+        assert!(!fhss.poll(late).unwrap());
+        assert!(!fhss.poll(late + dwell / 2).unwrap());
+
+        // No hopping once stopped
+        fhss.stop_fhss().unwrap();
+        assert!(!fhss.poll(late + dwell * 4).unwrap());
+
+        fhss.into_inner().done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_power() {
+        let mut radio = MockRadio::new(&[Transaction::set_power(10, None)]);
+
+        let _res = radio.set_power(10).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_frequency() {
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::Frequency(868_100_000), None),
+            Transaction::set_option(ConfigOption::Frequency(433_920_000), None),
+        ]);
+
+        // Without a declared range frequencies are passed to the radio
+        radio.set_frequency(868_100_000).unwrap();
+
+        // In-range frequencies are applied
+        radio.set_frequency_range(Some((433_050_000, 434_790_000)));
+        radio.set_frequency(433_920_000).unwrap();
+
+        // Out-of-range frequencies are rejected without being applied
+        assert_eq!(
+            radio.set_frequency(868_100_000),
+            Err(ConfigError::OutOfRange)
+        );
+        assert_eq!(
+            radio.apply_all(&[ConfigOption::Frequency(915_000_000)]),
+            Err((0, ConfigError::OutOfRange))
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_option() {
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PreambleLength(16), None),
+            Transaction::set_option(
+                ConfigOption::PreambleLength(32),
+                Some(ConfigError::NotSupported),
+            ),
+            Transaction::set_option(ConfigOption::PaSelect(PaKind::HighPower), None),
+            Transaction::set_option(
+                ConfigOption::SyncWords(
+                    SyncWords::from_slices(&[&[0x12, 0x34], &[0x56, 0x78]]).unwrap(),
+                ),
+                None,
+            ),
+            Transaction::set_option(ConfigOption::RxBoost(true), None),
+            Transaction::set_option(ConfigOption::XtalTrim(0x12), None),
+            Transaction::set_option(ConfigOption::BitOrder(BitOrder::LsbFirst), None),
+            Transaction::set_option(
+                ConfigOption::RxDutyCycle {
+                    rx: Duration::from_millis(2),
+                    sleep: Duration::from_millis(100),
+                },
+                None,
+            ),
+            Transaction::set_option(
+                ConfigOption::Whitening(WhiteningMode::Custom {
+                    poly: 0x0021,
+                    seed: 0x01ff,
+                }),
+                None,
+            ),
+            Transaction::set_option(
+                ConfigOption::Tcxo {
+                    voltage_mv: 1800,
+                    startup: Duration::from_millis(5),
+                },
+                None,
+            ),
+            Transaction::set_option(
+                ConfigOption::AutoRetransmit {
+                    count: 3,
+                    delay: Duration::from_micros(500),
+                },
+                None,
+            ),
+            Transaction::set_option(ConfigOption::SlicerThreshold(SlicerMode::Fixed(6)), None),
+            Transaction::set_option(ConfigOption::TempCompensation(true), None),
+            Transaction::set_option(ConfigOption::RxBandwidth(250_000), None),
+            Transaction::set_option(ConfigOption::LineCoding(LineCoding::Manchester), None),
+            Transaction::set_option(ConfigOption::Frequency(868_100_000), None),
+            Transaction::set_option(ConfigOption::Bandwidth(125_000), None),
+            Transaction::set_option(ConfigOption::SpreadingFactor(9), None),
+        ]);
+
+        radio.set_option(&ConfigOption::PreambleLength(16)).unwrap();
+
+        let res = radio.set_option(&ConfigOption::PreambleLength(32));
+        assert_eq!(res, Err(ConfigError::NotSupported));
+
+        radio
+            .set_option(&ConfigOption::PaSelect(PaKind::HighPower))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::SyncWords(
+                SyncWords::from_slices(&[&[0x12, 0x34], &[0x56, 0x78]]).unwrap(),
+            ))
+            .unwrap();
+
+        radio.set_option(&ConfigOption::RxBoost(true)).unwrap();
+
+        radio.set_option(&ConfigOption::XtalTrim(0x12)).unwrap();
+
+        radio
+            .set_option(&ConfigOption::BitOrder(BitOrder::LsbFirst))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::RxDutyCycle {
+                rx: Duration::from_millis(2),
+                sleep: Duration::from_millis(100),
+            })
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::Whitening(WhiteningMode::Custom {
+                poly: 0x0021,
+                seed: 0x01ff,
+            }))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::Tcxo {
+                voltage_mv: 1800,
+                startup: Duration::from_millis(5),
+            })
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::AutoRetransmit {
+                count: 3,
+                delay: Duration::from_micros(500),
+            })
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::SlicerThreshold(SlicerMode::Fixed(6)))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::TempCompensation(true))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::RxBandwidth(250_000))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::LineCoding(LineCoding::Manchester))
+            .unwrap();
+
+        radio
+            .set_option(&ConfigOption::Frequency(868_100_000))
+            .unwrap();
+
+        radio.set_option(&ConfigOption::Bandwidth(125_000)).unwrap();
+
+        radio.set_option(&ConfigOption::SpreadingFactor(9)).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_config_snapshot() {
+        use crate::config::ConfigSnapshot;
+
+        // Only MTU (read-only), PA selection and TX power are supported
+        let mut expectations: Vec<_> = ConfigSnapshot::templates()
+            .iter()
+            .map(|t| {
+                let res = match t {
+                    ConfigOption::MTU(_) => Ok(ConfigOption::MTU(128)),
+                    ConfigOption::PaSelect(_) => Ok(ConfigOption::PaSelect(PaKind::HighPower)),
+                    ConfigOption::TXPower(_) => Ok(ConfigOption::TXPower(20)),
+                    _ => Err(ConfigError::NotSupported),
+                };
+                Transaction::get_option(t.clone(), res)
+            })
+            .collect();
+
+        // Read-only options are skipped, and the PA is selected prior to setting TX power
+        expectations.push(Transaction::set_option(
+            ConfigOption::MTU(128),
+            Some(ConfigError::NotSupported),
+        ));
+        expectations.push(Transaction::set_option(
+            ConfigOption::PaSelect(PaKind::HighPower),
+            None,
+        ));
+        expectations.push(Transaction::set_option(ConfigOption::TXPower(20), None));
+
+        let mut radio = MockRadio::new(&expectations);
+
+        let snapshot = radio.snapshot().unwrap();
+        assert_eq!(snapshot.len(), 3);
+
+        radio.restore(&snapshot).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_option() {
+        let mut radio = MockRadio::new(&[Transaction::get_option(
+            ConfigOption::PreambleLength(0),
+            Ok(ConfigOption::PreambleLength(8)),
+        )]);
+
+        let mut o = ConfigOption::PreambleLength(0);
+        radio.get_option(&mut o).unwrap();
+        assert_eq!(o, ConfigOption::PreambleLength(8));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_effective_mtu() {
+        let mut radio = MockRadio::new(&[
+            Transaction::effective_mtu(Ok(64)),
+            Transaction::effective_mtu(Err(ConfigError::NotSupported)),
+        ]);
+
+        assert_eq!(radio.effective_mtu(), Ok(64));
+        assert_eq!(radio.effective_mtu(), Err(ConfigError::NotSupported));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_start_transmit() {
+        let mut radio =
+            MockRadio::new(&[Transaction::start_transmit(vec![0xaa, 0xbb, 0xcc], None)]);
+
+        let _res = radio.start_transmit(&[0xaa, 0xbb, 0xcc]).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_check_transmit() {
+        let mut radio = MockRadio::new(&[
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        let res = radio.check_transmit().unwrap();
+        assert_eq!(false, res);
+
+        let res = radio.check_transmit().unwrap();
+        assert_eq!(true, res);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_burst() {
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::delay_ns(1_000_000),
+            Transaction::start_transmit(vec![0xcc, 0xdd], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        radio
+            .do_transmit_burst(
+                &[&[0xaa, 0xbb], &[0xcc, 0xdd]],
+                Duration::from_millis(1),
+                BlockingOptions::default(),
+            )
+            .unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_transmit_no_crc() {
+        use crate::helpers::{do_transmit_configured, ConfiguredTransmitOptions, TransmitOptions};
+
+        let options = ConfiguredTransmitOptions {
+            transmit: TransmitOptions {
+                data: vec![0xaa, 0xbb],
+                power: None,
+                period: None,
+                blocking_options: BlockingOptions::default(),
+            },
+            preamble_length: Some(16),
+            no_crc: true,
+        };
+
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PreambleLength(16), None),
+            Transaction::get_option(ConfigOption::Crc(true), Ok(ConfigOption::Crc(true))),
+            Transaction::set_option(ConfigOption::Crc(false), None),
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::set_option(ConfigOption::Crc(true), None),
+        ]);
+
+        do_transmit_configured(&mut radio, options.clone()).unwrap();
+
+        radio.done();
+
+        // CRC is restored where transmission fails
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PreambleLength(16), None),
+            Transaction::get_option(ConfigOption::Crc(true), Ok(ConfigOption::Crc(true))),
+            Transaction::set_option(ConfigOption::Crc(false), None),
+            Transaction::start_transmit(vec![0xaa, 0xbb], Some(MockError::Timeout)),
+            Transaction::set_option(ConfigOption::Crc(true), None),
+        ]);
+
+        assert!(do_transmit_configured(&mut radio, options.clone()).is_err());
+
+        radio.done();
+
+        // CRC is re-enabled where the previous setting cannot be read
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PreambleLength(16), None),
+            Transaction::get_option(ConfigOption::Crc(true), Err(ConfigError::NotSupported)),
+            Transaction::set_option(ConfigOption::Crc(false), None),
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::set_option(ConfigOption::Crc(true), None),
+        ]);
+
+        do_transmit_configured(&mut radio, options).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_echo_full_buffer() {
+        use crate::helpers::{do_echo, EchoOptions};
+        use clap::Parser;
+
+        let opts =
+            EchoOptions::try_parse_from(["echo", "--append-info", "--delay", "10us"]).unwrap();
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x11, 0x22, 0x33], BasicInfo::new(-72, 0)))),
+            Transaction::delay_ns(10_000),
+            // Frame fills the buffer, so info is not appended
+            Transaction::start_transmit(vec![0x11, 0x22, 0x33], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        let mut buff = [0u8; 3];
+        assert_eq!(do_echo(&mut radio, &mut buff, opts), Ok(3));
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_operation_configured() {
+        use crate::helpers::{do_operation_configured, Operation};
+        use clap::Parser;
+
+        let op = Operation::try_parse_from([
+            "radio",
+            "tx",
+            "--data",
+            "170",
+            "--preamble-length",
+            "16",
+            "--no-crc",
+        ])
+        .unwrap();
+
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PreambleLength(16), None),
+            Transaction::get_option(ConfigOption::Crc(true), Ok(ConfigOption::Crc(true))),
+            Transaction::set_option(ConfigOption::Crc(false), None),
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::set_option(ConfigOption::Crc(true), None),
+        ]);
+
+        do_operation_configured(&mut radio, op).unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_tx_then_rx() {
+        use crate::blocking::BlockingError;
+        use crate::helpers::tx_then_rx;
+
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_millis(1),
+            timeout: Duration::from_micros(25),
+        };
+
+        // Completion and receive state are polled at the minimum interval
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::delay_ns(10_000),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::get_state(Ok(MockState::Idle)),
+            Transaction::delay_ns(10_000),
+            Transaction::get_state(Ok(MockState::Receive)),
+        ]);
+
+        assert!(tx_then_rx(&mut radio, &[0xaa], opts.clone()).is_ok());
+
+        radio.done();
+
+        // Radios not entering receive time out
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::get_state(Ok(MockState::Idle)),
+            Transaction::delay_ns(10_000),
+            Transaction::get_state(Ok(MockState::Idle)),
+            Transaction::delay_ns(10_000),
+            Transaction::get_state(Ok(MockState::Idle)),
+        ]);
+
+        assert_eq!(
+            tx_then_rx(&mut radio, &[0xaa], opts),
+            Err(BlockingError::Timeout)
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_scan_receive() {
+        use crate::blocking::BlockingError;
+        use crate::helpers::scan_receive;
+
+        let info = BasicInfo::new(-80, 0);
+        let mut radio = MockRadio::new(&[
+            // Nothing on the first channel
+            Transaction::set_channel(1, None),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::delay_ns(100_000),
+            Transaction::check_receive(true, Ok(false)),
+            // Packet on the second channel
+            Transaction::set_channel(2, None),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0xaa], info.clone()))),
+        ]);
+
+        let mut buff = [0u8; 8];
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_micros(100),
+            timeout: Duration::from_millis(10),
+        };
+
+        let (n, i, ch) = scan_receive(
+            &mut radio,
+            &[1, 2],
+            Duration::from_micros(100),
+            &mut buff,
+            opts.clone(),
+        )
+        .unwrap();
+        assert_eq!(&buff[..n], &[0xaa]);
+        assert_eq!(i, info);
+        assert_eq!(ch, 1);
+
+        // Zero dwell is rejected rather than scanning forever
+        assert_eq!(
+            scan_receive(&mut radio, &[1, 2], Duration::ZERO, &mut buff, opts),
+            Err(BlockingError::InvalidOptions)
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_transmit_on() {
+        let mut radio = MockRadio::new(&[
+            Transaction::set_channel(5, None),
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        crate::helpers::transmit_on(&mut radio, &5, &[0xaa, 0xbb], BlockingOptions::default())
+            .unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_basic_irq() {
+        let mut radio = MockIrqRadio::new(&[
+            Transaction::get_irq(true, Ok(BasicIrq::RX_DONE | BasicIrq::CRC_ERROR)),
+            Transaction::get_irq(false, Ok(BasicIrq::empty())),
+        ]);
+
+        let irq = radio.get_interrupts(true).unwrap();
+        assert!(irq.rx_done());
+        assert!(irq.crc_error());
+        assert!(!irq.tx_done());
+
+        assert!(radio.get_interrupts(false).unwrap().is_empty());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_detection_timing() {
+        let timing = DetectionTiming {
+            preamble: Duration::from_micros(800),
+            sync_offset: Duration::from_micros(1200),
+        };
+
+        let mut radio = MockRadio::new(&[
+            Transaction::preamble_detected(Ok(true)),
+            Transaction::detection_timing(Ok(Some(timing))),
+            Transaction::detection_timing(Ok(None)),
+        ]);
+
+        assert!(radio.preamble_detected().unwrap());
+        assert_eq!(radio.detection_timing(), Ok(Some(timing)));
+        assert_eq!(radio.detection_timing(), Ok(None));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_device_status() {
+        let status = DeviceStatusFlags::PLL_LOCK | DeviceStatusFlags::COMMAND_ERROR;
+
+        let mut radio = MockRadio::new(&[
+            Transaction::get_status(Ok(status)),
+            Transaction::clear_status(DeviceStatusFlags::COMMAND_ERROR, None),
+            Transaction::get_status(Ok(DeviceStatusFlags::PLL_LOCK)),
+        ]);
+
+        let s = radio.get_status().unwrap();
+        assert!(s.contains(DeviceStatusFlags::COMMAND_ERROR));
+
+        radio
+            .clear_status(DeviceStatusFlags::COMMAND_ERROR)
+            .unwrap();
+        assert!(!radio
+            .get_status()
+            .unwrap()
+            .contains(DeviceStatusFlags::COMMAND_ERROR));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_register_dump() {
+        let mut radio = MockRadio::new(&[
+            Transaction::read_raw(0x10, Ok(0xaa)),
+            Transaction::read_burst(0x20, Ok(vec![0x01, 0x02, 0x03])),
+        ]);
+
+        assert_eq!(radio.read_raw(0x10), Ok(0xaa));
+
+        let mut out = [0u8; 4];
+        assert_eq!(radio.dump(0x20, 3, &mut out), Ok(3));
+        assert_eq!(&out[..3], &[0x01, 0x02, 0x03]);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_fifo_level() {
+        let mut radio = MockRadio::new(&[
+            Transaction::fifo_level(Ok((0, 64))),
+            Transaction::fifo_level(Ok((12, 20))),
+        ]);
+
+        assert_eq!(radio.fifo_level(), Ok((0, 64)));
+        assert_eq!(radio.fifo_level(), Ok((12, 20)));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_cad() {
+        let mut radio = MockRadio::new(&[
+            Transaction::start_cad(None),
+            Transaction::check_cad(Ok(None)),
+            Transaction::check_cad(Ok(Some(true))),
+        ]);
+
+        radio.start_cad().unwrap();
+        assert_eq!(None, radio.check_cad().unwrap());
+        assert_eq!(Some(true), radio.check_cad().unwrap());
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_cad_receive() {
+        use crate::helpers::cad_receive;
+
+        let mut radio = MockRadio::new(&[
+            // Activity detected, enter receive
+            Transaction::start_cad(None),
+            Transaction::check_cad(Ok(None)),
+            Transaction::delay_ns(100_000),
+            Transaction::check_cad(Ok(Some(true))),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0xaa, 0xbb], BasicInfo::new(-80, 0)))),
+            // Channel clear, no receive
+            Transaction::start_cad(None),
+            Transaction::check_cad(Ok(Some(false))),
+        ]);
+
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_micros(100),
+            timeout: Duration::from_millis(10),
+        };
+        let mut buff = [0u8; 16];
+
+        assert_eq!(
+            cad_receive(&mut radio, &mut buff, opts.clone()).unwrap(),
+            Some((2, BasicInfo::new(-80, 0)))
+        );
+        assert_eq!(&buff[..2], &[0xaa, 0xbb]);
+
+        assert_eq!(cad_receive(&mut radio, &mut buff, opts).unwrap(), None);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_start_receive() {
+        let mut radio = MockRadio::new(&[Transaction::start_receive(None)]);
+
+        let _res = radio.start_receive().unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_check_receive() {
+        let mut radio = MockRadio::new(&[
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::check_receive(true, Ok(true)),
+        ]);
+
+        let res = radio.check_receive(true).unwrap();
+        assert_eq!(false, res);
+
+        let res = radio.check_receive(true).unwrap();
+        assert_eq!(true, res);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_received() {
+        let mut radio = MockRadio::new(&[Transaction::get_received(Ok((
+            vec![0xaa, 0xbb],
+            BasicInfo::new(10, 12),
+        )))]);
+
+        let mut buff = vec![0u8; 3];
+
+        let (n, _i) = radio.get_received(&mut buff).unwrap();
+
+        assert_eq!(2, n);
+        assert_eq!(&buff[..2], &[0xaa, 0xbb]);
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_radio_mock_received_iter() {
+        use crate::blocking::{BlockingError, BlockingReceive};
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0xaa, 0xbb], BasicInfo::new(-80, 0)))),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Err(MockError::Timeout)),
+        ]);
+
+        let options = BlockingOptions {
+            timeout: Duration::from_micros(0),
+            ..Default::default()
+        };
+        let mut iter = radio.received_iter(16, options);
+
+        let (d, i) = iter.next().unwrap().unwrap();
+        assert_eq!(d, vec![0xaa, 0xbb]);
+        assert_eq!(i, BasicInfo::new(-80, 0));
+
+        assert_eq!(iter.next().unwrap(), Err(BlockingError::Timeout));
+        assert_eq!(
+            iter.next().unwrap(),
+            Err(BlockingError::Inner(MockError::Timeout))
+        );
+        assert!(iter.next().is_none());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_rx_overflow() {
+        let mut radio = MockRadio::new(&[
+            Transaction::rx_overflow(Ok(false)),
+            Transaction::rx_overflow(Ok(true)),
+        ]);
+
+        assert_eq!(false, radio.rx_overflow().unwrap());
+        assert_eq!(true, radio.rx_overflow().unwrap());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_last_rx_was_broadcast() {
+        let mut radio = MockRadio::new(&[
+            Transaction::last_rx_was_broadcast(Ok(Some(true))),
+            Transaction::last_rx_was_broadcast(Ok(None)),
+        ]);
+
+        assert_eq!(Some(true), radio.last_rx_was_broadcast().unwrap());
+        assert_eq!(None, radio.last_rx_was_broadcast().unwrap());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_poll_rssi_in_state() {
+        let mut radio = MockRadio::new(&[
+            Transaction::poll_rssi_in_state(Ok(None)),
+            Transaction::poll_rssi_in_state(Ok(Some(-90))),
+        ]);
+
+        assert_eq!(None, radio.poll_rssi_in_state().unwrap());
+        assert_eq!(Some(-90), radio.poll_rssi_in_state().unwrap());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_poll_rssi_packet() {
+        let mut radio = MockRadio::new(&[
+            Transaction::poll_rssi(Ok(-110)),
+            Transaction::poll_rssi_packet(Ok(Some(-72))),
+        ]);
+
+        assert_eq!(-110, radio.poll_rssi().unwrap());
+        assert_eq!(Some(-72), radio.poll_rssi_packet().unwrap());
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_then_receive() {
+        use crate::blocking::BlockingReceive;
+
+        let info = BasicInfo::new(-80, 0);
+        let mut radio = MockRadio::transmit_then_receive(&[0xaa], &[0xbb, 0xcc], info.clone());
+
+        radio
+            .do_transmit(&[0xaa], BlockingOptions::default())
+            .unwrap();
+
+        let mut buff = [0u8; 8];
+        let (n, i) = radio
+            .do_receive(&mut buff, BlockingOptions::default())
+            .unwrap();
+        assert_eq!(&buff[..n], &[0xbb, 0xcc]);
+        assert_eq!(i, info);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_rssi_scale() {
+        let mut radio = MockRadio::new(&[]);
+        assert_eq!(radio.rssi_scale(), RssiScale::Dbm);
+
+        let scale = RssiScale::Scaled {
+            step_cdb: 50,
+            offset_dbm: -164,
+        };
+        radio.set_rssi_scale(scale);
+        assert_eq!(radio.rssi_scale(), scale);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_received_into_info() {
+        let mut radio = MockRadio::new(&[Transaction::get_received(Ok((
+            vec![0xaa, 0xbb],
+            BasicInfo::new(-80, 10),
+        )))]);
+
+        let mut info = BasicInfo::default();
+        let mut buff = [0u8; 4];
+
+        let n = radio.get_received_into_info(&mut info, &mut buff).unwrap();
+        assert_eq!(&buff[..n], &[0xaa, 0xbb]);
+        assert_eq!(info, BasicInfo::new(-80, 10));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_permissive_delay() {
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+        radio.set_permissive_delay(true);
+
+        radio
+            .do_transmit(&[0xaa, 0xbb], BlockingOptions::default())
+            .unwrap();
+
+        assert_eq!(radio.total_delay_ns(), 2 * 100 * 1000);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_zero_poll_interval() {
+        use crate::blocking::{BlockingError, OptionsError, MIN_POLL_INTERVAL};
+
+        assert_eq!(
+            BlockingOptions::new(Duration::ZERO, Duration::from_millis(1)),
+            Err(OptionsError::ZeroPollInterval)
+        );
+
+        // Zero intervals are clamped so timeouts still elapse
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(false)),
+        ]);
+        radio.set_permissive_delay(true);
+
+        let opts = BlockingOptions {
+            poll_interval: Duration::ZERO,
+            timeout: MIN_POLL_INTERVAL * 2,
+        };
+        assert_eq!(
+            radio.do_transmit(&[0xaa], opts),
+            Err(BlockingError::Timeout)
+        );
+        assert_eq!(
+            radio.total_delay_ns(),
+            2 * MIN_POLL_INTERVAL.as_nanos() as u64
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_at() {
+        let mut radio = MockRadio::new(&[
+            Transaction::transmit_at(vec![0xaa, 0xbb], 1_000, None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::delay_ns(500 * 1000),
+            Transaction::start_transmit(vec![0xcc], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        // Hardware scheduled
+        radio.transmit_at(&[0xaa, 0xbb], 1_000).unwrap();
+        assert_eq!(true, radio.check_transmit().unwrap());
+
+        // Emulated
+        radio
+            .do_transmit_at(&[0xcc], 1_500, 1_000, BlockingOptions::default())
+            .unwrap();
+
+        radio.done();
+    }
+
+    /// Mock radio wrapper recording total delay, for testing long delays which
+    /// are split into many delay calls
+    struct Timed(MockRadio, u64);
+
+    impl Transmit for Timed {
+        type Error = MockError;
+
+        fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+            self.0.start_transmit(data)
+        }
+
+        fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+            self.0.check_transmit()
+        }
+    }
+
+    impl DelayNs for Timed {
+        fn delay_ns(&mut self, ns: u32) {
+            self.1 += ns as u64;
+        }
+    }
+
+    impl Rssi for Timed {
+        type Error = MockError;
+
+        fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+            self.0.poll_rssi()
+        }
+    }
+
+    // Default (emulated) sampling
+    impl Sampler for Timed {}
+
+    #[test]
+    fn test_radio_mock_transmit_at_long() {
+        let mut radio = Timed(
+            MockRadio::new(&[
+                Transaction::start_transmit(vec![0xcc], None),
+                Transaction::check_transmit(Ok(true)),
+            ]),
+            0,
+        );
+
+        // Gap exceeding u32::MAX microseconds
+        let gap = u32::MAX as u64 + 1_000;
+        radio
+            .do_transmit_at(&[0xcc], 1_000 + gap, 1_000, BlockingOptions::default())
+            .unwrap();
+
+        assert_eq!(radio.1, gap * 1_000);
+
+        radio.0.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_burst_long() {
+        let mut radio = Timed(
+            MockRadio::new(&[
+                Transaction::start_transmit(vec![0xaa], None),
+                Transaction::check_transmit(Ok(true)),
+                Transaction::start_transmit(vec![0xbb], None),
+                Transaction::check_transmit(Ok(true)),
+            ]),
+            0,
+        );
+
+        // Gap exceeding u32::MAX microseconds
+        let gap = u32::MAX as u64 + 1_000;
+        radio
+            .do_transmit_burst(
+                &[&[0xaa], &[0xbb]],
+                Duration::from_micros(gap),
+                BlockingOptions::default(),
+            )
+            .unwrap();
+
+        assert_eq!(radio.1, gap * 1_000);
+
+        radio.0.done();
+    }
+
+    #[test]
+    fn test_radio_mock_capture_rssi_long() {
+        let mut radio = Timed(
+            MockRadio::new(&[
+                Transaction::poll_rssi(Ok(-90)),
+                Transaction::poll_rssi(Ok(-85)),
+            ]),
+            0,
+        );
+
+        // Interval exceeding u32::MAX microseconds
+        let interval = u32::MAX as u64 + 1_000;
+        let mut samples = [0i16; 2];
+        assert_eq!(
+            radio.capture_rssi(&mut samples, Duration::from_micros(interval)),
+            Ok(2)
+        );
+        assert_eq!(samples, [-90, -85]);
+
+        assert_eq!(radio.1, interval * 1_000);
+
+        radio.0.done();
+    }
+
+    #[test]
+    fn test_radio_mock_receive_diag() {
+        use crate::blocking::{BlockingError, BlockingReceiveDiagnostics, ReceiveDiagnostics};
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::poll_rssi(Ok(-95)),
+            Transaction::delay_ns(100 * 1000),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::poll_rssi(Ok(-70)),
+        ]);
+
+        let mut buff = [0u8; 16];
+        let mut diag = ReceiveDiagnostics::default();
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_micros(100),
+            timeout: Duration::from_micros(150),
+        };
+
+        assert_eq!(
+            radio.do_receive_diag(&mut buff, opts, &mut diag),
+            Err(BlockingError::Timeout)
+        );
+        assert_eq!(
+            diag,
+            ReceiveDiagnostics {
+                peak_rssi: Some(-70),
+                rssi_samples: 2,
+                preamble_detections: 0,
+            }
+        );
+
+        radio.done();
+
+        // Preamble detections are counted once per detection
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::poll_rssi(Ok(-95)),
+            Transaction::preamble_detected(Ok(false)),
+            Transaction::delay_ns(100 * 1000),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::poll_rssi(Ok(-70)),
+            Transaction::preamble_detected(Ok(true)),
+            Transaction::delay_ns(100 * 1000),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::poll_rssi(Ok(-72)),
+            Transaction::preamble_detected(Ok(true)),
+        ]);
+
+        let mut diag = ReceiveDiagnostics::default();
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_micros(100),
+            timeout: Duration::from_micros(250),
+        };
+
+        assert_eq!(
+            radio.do_receive_diag_preamble(&mut buff, opts, &mut diag),
+            Err(BlockingError::Timeout)
+        );
+        assert_eq!(
+            diag,
+            ReceiveDiagnostics {
+                peak_rssi: Some(-70),
+                rssi_samples: 3,
+                preamble_detections: 1,
+            }
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_received_packet() {
+        let info = BasicInfo::new(-81, 0);
+        let mut radio = MockRadio::new(&[Transaction::get_received(Ok((
+            vec![0xaa, 0xbb],
+            info.clone(),
         )))]);
 
-        let mut buff = vec![0u8; 3];
+        let (p, i) = radio.get_received_packet::<16>().unwrap();
+        assert_eq!(p.as_slice(), &[0xaa, 0xbb]);
+        assert_eq!(i, info);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_set_power_pct() {
+        let mut radio = MockRadio::new(&[
+            Transaction::set_power(-18, None),
+            Transaction::set_power(-2, None),
+            Transaction::set_power(13, None),
+        ]);
+
+        // Unknown range does not change power
+        assert_eq!(radio.set_power_pct(50), Ok(None));
+
+        radio.set_power_range(Some((-18, 13)));
+
+        assert_eq!(radio.set_power_pct(0), Ok(Some(-18)));
+        assert_eq!(radio.set_power_pct(50), Ok(Some(-2)));
+        assert_eq!(radio.set_power_pct(150), Ok(Some(13)));
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "nonblocking")]
+    fn test_radio_mock_async_poll_count() {
+        use core::future::Future;
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+
+        use crate::nonblocking::{AsyncOptions, AsyncReceive};
+
+        let info = BasicInfo::new(-81, 0);
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0xaa], info.clone()))),
+        ]);
+
+        let mut buff = [0u8; 16];
+        let mut cx = Context::from_waker(Waker::noop());
+
+        {
+            let mut f = radio
+                .async_receive(&mut buff, AsyncOptions::default())
+                .unwrap();
+            assert_eq!(f.poll_count(), 0);
+
+            for i in 1..3 {
+                assert!(Pin::new(&mut f).poll(&mut cx).is_pending());
+                assert_eq!(f.poll_count(), i);
+            }
+
+            assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Ready(Ok((1, info))));
+            assert_eq!(f.poll_count(), 3);
+        }
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_measure_transitions() {
+        use crate::helpers::measure_transitions;
+
+        let mut expectations = vec![];
+        for s in [
+            MockState::Idle,
+            MockState::Receive,
+            MockState::Idle,
+            MockState::Transmitting,
+            MockState::Idle,
+            MockState::Sleep,
+            MockState::Idle,
+        ] {
+            expectations.push(Transaction::set_state(s, None));
+            expectations.push(Transaction::get_state(Ok(s)));
+        }
+
+        let mut radio = MockRadio::new(&expectations);
+
+        let l = measure_transitions(
+            &mut radio,
+            MockState::Receive,
+            MockState::Transmitting,
+            BlockingOptions::default(),
+        )
+        .unwrap();
+
+        assert!(l.idle_to_rx < Duration::from_secs(1));
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_estimate_clock_drift() {
+        use crate::helpers::{estimate_clock_drift, InfoFormat, RemoteInfo};
+
+        #[derive(Debug, Clone, PartialEq, Default)]
+        struct TsInfo(u64);
+
+        impl ReceiveInfo for TsInfo {
+            fn rssi(&self) -> i16 {
+                0
+            }
+
+            fn timestamp(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+
+        // Remote clock runs 1000 ppm fast with a 4000us offset
+        let mut expectations = vec![];
+        for (i, (local, remote)) in [(1_000u64, 5_000u64), (2_000, 6_001), (3_000, 7_002)]
+            .iter()
+            .enumerate()
+        {
+            let mut resp = (i as u32).to_be_bytes().to_vec();
+            let mut info = [0u8; 16];
+            let n = RemoteInfo {
+                timestamp: Some(*remote),
+                ..Default::default()
+            }
+            .encode(InfoFormat::Extended, &mut info);
+            resp.extend_from_slice(&info[..n]);
+
+            expectations.extend_from_slice(&[
+                Transaction::start_transmit((i as u32).to_be_bytes().to_vec(), None),
+                Transaction::check_transmit(Ok(true)),
+                Transaction::start_receive(None),
+                Transaction::check_receive(true, Ok(true)),
+                Transaction::get_received(Ok((resp, TsInfo(*local)))),
+            ]);
+        }
+
+        let mut radio = Radio::<MockState, u8, u8, TsInfo, u8, MockError>::new(&expectations);
+
+        let d = estimate_clock_drift(&mut radio, 3, BlockingOptions::default())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(d.samples, 3);
+        assert_eq!(d.offset_us, 4_002);
+        assert!((d.drift_ppm - 1000.0).abs() < 1e-6);
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_power_sweep() {
+        use crate::helpers::{power_sweep, InfoFormat, PowerSweepOptions, PowerSweepPoint};
+
+        let mut expectations = vec![];
+        for (p, rssi) in [(0i8, -90i16), (10, -80)] {
+            let mut resp = vec![0, 0, 0, 0];
+            resp.extend_from_slice(&rssi.to_be_bytes());
+
+            expectations.extend_from_slice(&[
+                Transaction::set_power(p, None),
+                Transaction::start_transmit(vec![0, 0, 0, 0], None),
+                Transaction::check_transmit(Ok(true)),
+                Transaction::start_receive(None),
+                Transaction::check_receive(true, Ok(true)),
+                Transaction::get_received(Ok((resp, BasicInfo::new(-70, 0)))),
+            ]);
+        }
+
+        let mut radio = MockRadio::new(&expectations);
+        radio.set_permissive_delay(true);
+
+        let points = power_sweep(
+            &mut radio,
+            &[0, 10],
+            PowerSweepOptions {
+                rounds: 1,
+                delay: "1ms".parse().unwrap(),
+                info_format: InfoFormat::Rssi,
+                blocking_options: BlockingOptions::default(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].remote_rssi.mean, -90.0);
+        assert_eq!(points[1].received, 1);
+        assert_eq!(points[1].to_csv(), "10,1,1,-80.0,-80,-80");
+        assert_eq!(
+            PowerSweepPoint::CSV_HEADER.split(',').count(),
+            points[1].to_csv().split(',').count()
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_pa_controlled() {
+        use crate::blocking::BlockingOptions;
+        use crate::helpers::{tx_then_rx, PaControlledRadio};
+        use std::{cell::RefCell, rc::Rc};
+
+        // Pin recording level changes
+        struct Pin(Rc<RefCell<Vec<bool>>>);
+
+        impl embedded_hal::digital::ErrorType for Pin {
+            type Error = core::convert::Infallible;
+        }
+
+        impl embedded_hal::digital::OutputPin for Pin {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0.borrow_mut().push(false);
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.0.borrow_mut().push(true);
+                Ok(())
+            }
+        }
+
+        let levels = Rc::new(RefCell::new(vec![]));
+        let radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(false)),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_transmit(vec![0xbb], Some(MockError::Timeout)),
+            Transaction::start_transmit(vec![0xcc], None),
+            Transaction::check_transmit(Err(MockError::Timeout)),
+            Transaction::set_channel(3, None),
+            Transaction::poll_rssi(Ok(-90)),
+            Transaction::set_option(ConfigOption::Crc(true), None),
+            Transaction::start_transmit(vec![0xdd], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::get_state(Ok(MockState::Receive)),
+        ]);
+
+        let mut radio = PaControlledRadio::new(radio, Pin(levels.clone())).unwrap();
+        assert_eq!(*levels.borrow(), [false]);
 
-        let (n, _i) = radio.get_received(&mut buff).unwrap();
+        // PA asserted for the duration of the transmission
+        radio.start_transmit(&[0xaa]).unwrap();
+        assert_eq!(*levels.borrow(), [false, true]);
 
-        assert_eq!(2, n);
+        assert!(!radio.check_transmit().unwrap());
+        assert_eq!(*levels.borrow(), [false, true]);
+
+        assert!(radio.check_transmit().unwrap());
+        assert_eq!(*levels.borrow(), [false, true, false]);
+
+        // PA deasserted where transmit fails to start
+        assert!(radio.start_transmit(&[0xbb]).is_err());
+        assert_eq!(*levels.borrow(), [false, true, false, true, false]);
+
+        // PA deasserted where checking transmit fails
+        levels.borrow_mut().clear();
+        radio.start_transmit(&[0xcc]).unwrap();
+        assert!(radio.check_transmit().is_err());
+        assert_eq!(*levels.borrow(), [true, false]);
+
+        // Other operations are delegated without changing the PA state
+        radio.set_channel(&3).unwrap();
+        assert_eq!(radio.poll_rssi().unwrap(), -90);
+        radio.set_option(&ConfigOption::Crc(true)).unwrap();
+        assert_eq!(*levels.borrow(), [true, false]);
+
+        // Combined helpers drive the wrapper through the shared error type
+        levels.borrow_mut().clear();
+        tx_then_rx(&mut radio, &[0xdd], BlockingOptions::default()).unwrap();
+        assert_eq!(*levels.borrow(), [true, false]);
+
+        radio.into_inner().0.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_receive_auto() {
+        use crate::helpers::receive_auto;
+
+        // Pin returning a fixed sequence of levels
+        struct Pin(Vec<bool>);
+
+        impl embedded_hal::digital::ErrorType for Pin {
+            type Error = core::convert::Infallible;
+        }
+
+        impl embedded_hal::digital::InputPin for Pin {
+            fn is_high(&mut self) -> Result<bool, Self::Error> {
+                Ok(self.0.remove(0))
+            }
+
+            fn is_low(&mut self) -> Result<bool, Self::Error> {
+                self.is_high().map(|v| !v)
+            }
+        }
+
+        let info = BasicInfo::new(-80, 0);
+        let mut buff = [0u8; 16];
+
+        // With an IRQ pin the radio is only checked once the IRQ is asserted
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::delay_ns(100_000),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x11, 0x22], info.clone()))),
+        ]);
+
+        let mut pin = Pin(vec![false, true]);
+        let (n, i) = receive_auto(
+            &mut radio,
+            Some(&mut pin),
+            &mut buff,
+            BlockingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(&buff[..n], &[0x11, 0x22]);
+        assert_eq!(i, info);
+        radio.done();
+
+        // Without a pin the radio is polled
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::delay_ns(100_000),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x33], info.clone()))),
+        ]);
+
+        let (n, _i) = receive_auto(
+            &mut radio,
+            None::<&mut Pin>,
+            &mut buff,
+            BlockingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(&buff[..n], &[0x33]);
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_rx_pipeline() {
+        use crate::helpers::{RxPipeline, RxPipelineStats};
+
+        let strong = BasicInfo::new(-70, 0);
+        let weak = BasicInfo::new(-100, 0);
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x01], strong.clone()))),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x02], weak.clone()))),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x01], strong.clone()))),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x03], strong.clone()))),
+        ]);
+
+        let mut forwarded = vec![];
+        let mut buff = [0u8; 16];
+
+        let stats = RxPipeline::new()
+            .filter(|_d, i: &BasicInfo| i.rssi() > -90)
+            .dedup(4)
+            .sink(|d, _i| forwarded.push(d.to_vec()))
+            .run(&mut radio, &mut buff, 4, BlockingOptions::default())
+            .unwrap()
+            .clone();
+
+        assert_eq!(
+            stats,
+            RxPipelineStats {
+                received: 4,
+                filtered: 1,
+                duplicates: 1,
+                forwarded: 2,
+            }
+        );
+        assert_eq!(forwarded, vec![vec![0x01], vec![0x03]]);
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_guarded() {
+        use crate::helpers::{GuardError, GuardState, GuardedRadio};
+
+        let radio = MockRadio::new(&[
+            Transaction::set_state(MockState::Sleep, None),
+            Transaction::set_state(MockState::Idle, None),
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::poll_rssi(Ok(-90)),
+        ]);
+
+        let mut radio = GuardedRadio::new(radio);
+
+        // Illegal operations are rejected without reaching the radio
+        radio.set_state(MockState::Sleep).unwrap();
+        assert_eq!(
+            radio.start_transmit(&[0xaa]),
+            Err(GuardError::IllegalTransition {
+                state: GuardState::Sleep,
+                operation: "start_transmit"
+            })
+        );
+
+        radio.set_state(MockState::Idle).unwrap();
+        assert!(matches!(
+            radio.poll_rssi(),
+            Err(GuardError::IllegalTransition { .. })
+        ));
+
+        // Legal operations are forwarded
+        radio.start_transmit(&[0xaa]).unwrap();
+        assert_eq!(radio.state(), GuardState::Transmitting);
+        assert_eq!(radio.check_transmit(), Ok(true));
+        assert_eq!(radio.state(), GuardState::Idle);
+
+        radio.start_receive().unwrap();
+        assert_eq!(radio.poll_rssi(), Ok(-90));
+
+        radio.into_inner().done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_persistent_config() {
+        use crate::helpers::PersistentConfigRadio;
+
+        let radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::PAN(1), None),
+            Transaction::set_option(ConfigOption::TXPower(10), None),
+            Transaction::set_option(ConfigOption::RxBoost(true), Some(ConfigError::NotSupported)),
+            Transaction::set_option(ConfigOption::PAN(2), None),
+            // Replay on request
+            Transaction::set_option(ConfigOption::PAN(2), None),
+            Transaction::set_option(ConfigOption::TXPower(10), None),
+            // No reset reported
+            Transaction::get_status(Ok(DeviceStatusFlags::PLL_LOCK)),
+            // Reset reported, replay and clear
+            Transaction::get_status(Ok(DeviceStatusFlags::RESET)),
+            Transaction::set_option(ConfigOption::PAN(2), None),
+            Transaction::set_option(ConfigOption::TXPower(10), None),
+            Transaction::clear_status(DeviceStatusFlags::RESET, None),
+            // Replay on wakeup
+            Transaction::shutdown(None),
+            Transaction::wakeup(None),
+            Transaction::set_option(ConfigOption::PAN(2), None),
+            Transaction::set_option(ConfigOption::TXPower(10), None),
+            // Other operations are delegated
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::get_state(Ok(MockState::Receive)),
+        ]);
+
+        let mut radio = PersistentConfigRadio::new(radio);
+
+        radio.set_option(&ConfigOption::PAN(1)).unwrap();
+        radio.set_option(&ConfigOption::TXPower(10)).unwrap();
+        assert!(radio.set_option(&ConfigOption::RxBoost(true)).is_err());
+        radio.set_option(&ConfigOption::PAN(2)).unwrap();
+
+        assert_eq!(
+            radio.options(),
+            &[ConfigOption::PAN(2), ConfigOption::TXPower(10)]
+        );
+
+        radio.reapply().unwrap();
+
+        assert_eq!(radio.check_reset(), Ok(false));
+        assert_eq!(radio.check_reset(), Ok(true));
+
+        radio.shutdown().unwrap();
+        radio.wakeup().unwrap();
+
+        radio.start_transmit(&[0xaa, 0xbb]).unwrap();
+        assert_eq!(radio.check_transmit(), Ok(true));
+        radio.start_receive().unwrap();
+        assert_eq!(radio.get_state(), Ok(MockState::Receive));
+
+        radio.into_inner().done();
+    }
+
+    #[test]
+    fn test_radio_mock_capture_rssi() {
+        let interval = Duration::from_millis(1);
+        let mut radio = MockRadio::new(&[Transaction::capture_rssi(
+            4,
+            interval,
+            Ok(vec![-90, -85, -60, -88]),
+        )]);
+
+        let mut samples = [0i16; 4];
+        assert_eq!(radio.capture_rssi(&mut samples, interval), Ok(4));
+        assert_eq!(samples, [-90, -85, -60, -88]);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_received_raw() {
+        let info = BasicInfo::new(-81, 0);
+        let mut radio = MockRadio::new(&[
+            Transaction::check_receive(false, Ok(true)),
+            Transaction::get_received_raw(Ok((vec![0xaa, 0xbb], info.clone(), false))),
+        ]);
+
+        let mut buff = [0u8; 16];
+
+        assert_eq!(radio.check_receive_once(), Ok(true));
+        assert_eq!(radio.get_received_raw(&mut buff), Ok((2, info, false)));
         assert_eq!(&buff[..2], &[0xaa, 0xbb]);
 
         radio.done();
     }
+
+    #[test]
+    fn test_radio_mock_clock_receive_timeout() {
+        use crate::blocking::{BlockingError, BlockingReceive};
+
+        let mut expectations = vec![Transaction::start_receive(None)];
+        for _ in 0..11 {
+            expectations.push(Transaction::check_receive(true, Ok(false)));
+        }
+
+        let mut radio = MockRadio::new(&expectations);
+        radio.set_permissive_delay(true);
+        let clock = radio.clock();
+
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(100),
+        };
+
+        let mut buff = [0u8; 16];
+        assert_eq!(
+            radio.do_receive(&mut buff, opts),
+            Err(BlockingError::Timeout)
+        );
+        assert_eq!(clock.now(), Duration::from_millis(100));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_apply_all_preset() {
+        use crate::config::RegionalPreset;
+
+        let preset = RegionalPreset::EU868;
+        let options = preset.options();
+
+        let mut radio = MockRadio::new(&[
+            Transaction::set_option(options[0].clone(), None),
+            Transaction::set_option(options[1].clone(), None),
+            Transaction::set_option(options[2].clone(), Some(ConfigError::NotSupported)),
+        ]);
+
+        assert_eq!(options[0], ConfigOption::Frequency(868_100_000));
+        assert_eq!(options[1], ConfigOption::Bandwidth(125_000));
+        assert_eq!(options[2], ConfigOption::SpreadingFactor(7));
+        assert!(!options
+            .iter()
+            .any(|o| matches!(o, ConfigOption::TXPower(_))));
+        assert_eq!(
+            radio.apply_all(&options),
+            Err((2, ConfigError::NotSupported))
+        );
+
+        // Presets tune within the radio frequency range
+        radio.set_frequency_range(Some((433_050_000, 434_790_000)));
+        assert_eq!(radio.apply_all(&options), Err((0, ConfigError::OutOfRange)));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_monitor_tx() {
+        let interval = Duration::from_micros(10);
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::monitor_tx(8, interval, Ok(vec![-20, -5, 5, 12, 14, 14])),
+        ]);
+
+        radio.start_transmit(&[0xaa]).unwrap();
+
+        let mut samples = [0i16; 8];
+        assert_eq!(radio.monitor_tx(&mut samples, interval), Ok(6));
+        assert_eq!(&samples[..6], &[-20, -5, 5, 12, 14, 14]);
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_state_watcher() {
+        use crate::helpers::StateWatcher;
+
+        let radio = MockRadio::new(&[
+            Transaction::set_state(MockState::Idle, None),
+            Transaction::get_state(Ok(MockState::Idle)),
+            Transaction::set_state(MockState::Receive, None),
+            Transaction::get_state(Ok(MockState::Receiving)),
+        ]);
+
+        let mut transitions = vec![];
+        {
+            let mut radio = StateWatcher::new(radio, |from: Option<&MockState>, to: &MockState| {
+                transitions.push((from.cloned(), *to))
+            });
+
+            radio.set_state(MockState::Idle).unwrap();
+            radio.get_state().unwrap();
+            radio.set_state(MockState::Receive).unwrap();
+            radio.get_state().unwrap();
+
+            radio.into_inner().done();
+        }
+
+        assert_eq!(
+            transitions,
+            vec![
+                (None, MockState::Idle),
+                (Some(MockState::Idle), MockState::Receive),
+                (Some(MockState::Receive), MockState::Receiving),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_radio_mock_drain_received() {
+        let info = BasicInfo::new(-81, 0);
+        let mut radio = MockRadio::new(&[Transaction::drain_received(Ok(vec![
+            (vec![0x01], info.clone()),
+            (vec![0x02, 0x03], info.clone()),
+        ]))]);
+
+        let mut buff = [0u8; 16];
+        let mut frames = vec![];
+
+        let n = radio
+            .drain_received(&mut buff, |d, _i| frames.push(d.to_vec()))
+            .unwrap();
+
+        assert_eq!(n, 2);
+        assert_eq!(frames, vec![vec![0x01], vec![0x02, 0x03]]);
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_remaining() {
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa, 0xbb], None),
+            Transaction::transmit_remaining(Ok(Some(Duration::from_micros(250)))),
+            Transaction::transmit_remaining(Ok(None)),
+        ]);
+
+        radio.start_transmit(&[0xaa, 0xbb]).unwrap();
+
+        assert_eq!(
+            radio.transmit_remaining(),
+            Ok(Some(Duration::from_micros(250)))
+        );
+        assert_eq!(radio.transmit_remaining(), Ok(None));
+
+        radio.done();
+    }
+
+    #[test]
+    fn test_radio_mock_transmit_preamble() {
+        let mut radio = MockRadio::new(&[
+            Transaction::transmit_preamble(Duration::from_millis(100), None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::transmit_preamble(
+                Duration::from_millis(100),
+                Some(ConfigError::NotSupported),
+            ),
+        ]);
+
+        radio.transmit_preamble(Duration::from_millis(100)).unwrap();
+        assert!(radio.check_transmit().unwrap());
+
+        assert_eq!(
+            radio.transmit_preamble(Duration::from_millis(100)),
+            Err(ConfigError::NotSupported)
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_transmit_stream() {
+        use crate::helpers::{transmit_stream, StreamSummary};
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0x01, 0x02, 0x03], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_transmit(vec![0x04, 0x05, 0x06], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_transmit(vec![0x07], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        let data: &[u8] = &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+        let summary = transmit_stream(&mut radio, data, 3, BlockingOptions::default()).unwrap();
+
+        assert_eq!(
+            summary,
+            StreamSummary {
+                bytes: 7,
+                frames: 3
+            }
+        );
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_replay_pcap() {
+        use crate::helpers::{replay_pcap_reader, ReplayOptions};
+        use pcap_file::PcapWriter;
+
+        let mut w = PcapWriter::new(Vec::new()).unwrap();
+        w.write(1, 0, &[0x01, 0x02], 2).unwrap();
+        w.write(1, 2_000_000, &[0x03], 1).unwrap();
+        let capture = w.into_writer();
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0x01, 0x02], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::delay_ns(2_000_000),
+            Transaction::start_transmit(vec![0x03], None),
+            Transaction::check_transmit(Ok(true)),
+        ]);
+
+        let opts = ReplayOptions {
+            gap: None,
+            blocking_options: BlockingOptions::default(),
+        };
+        let n = replay_pcap_reader(&mut radio, capture.as_slice(), opts).unwrap();
+        assert_eq!(n, 2);
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_replay_pcap_long_gap() {
+        use crate::helpers::{replay_pcap_reader, ReplayOptions};
+        use pcap_file::PcapWriter;
+
+        // Timestamp gap exceeding u32::MAX microseconds
+        let mut w = PcapWriter::new(Vec::new()).unwrap();
+        w.write(0, 0, &[0x01], 1).unwrap();
+        w.write(5_000, 0, &[0x02], 1).unwrap();
+        let capture = w.into_writer();
+
+        let mut radio = Timed(
+            MockRadio::new(&[
+                Transaction::start_transmit(vec![0x01], None),
+                Transaction::check_transmit(Ok(true)),
+                Transaction::start_transmit(vec![0x02], None),
+                Transaction::check_transmit(Ok(true)),
+            ]),
+            0,
+        );
+
+        let opts = ReplayOptions {
+            gap: None,
+            blocking_options: BlockingOptions::default(),
+        };
+        let n = replay_pcap_reader(&mut radio, capture.as_slice(), opts).unwrap();
+        assert_eq!(n, 2);
+        assert_eq!(radio.1, 5_000 * 1_000_000_000);
+
+        radio.0.done();
+    }
+
+    #[test]
+    fn test_radio_mock_state_after() {
+        use crate::blocking::BlockingStateAfter;
+
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::set_state(MockState::Sleep, None),
+            Transaction::get_state(Ok(MockState::Sleep)),
+        ]);
+
+        let opts = BlockingOptions::default();
+        radio
+            .with_state_after(Some(MockState::Sleep), opts.clone(), |r| {
+                r.do_transmit(&[0xaa], opts.clone())
+            })
+            .unwrap();
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_filtering_radio() {
+        use crate::helpers::FilteringRadio;
+
+        let info = BasicInfo::new(-81, 0);
+        let radio = MockRadio::new(&[
+            Transaction::set_option(ConfigOption::Promiscuous(true), None),
+            Transaction::start_receive(None),
+            // Non-matching frame is dropped
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x00, 0x12, 0x35, 0xaa], info.clone()))),
+            Transaction::start_receive(None),
+            // Matching frame is passed through
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x00, 0x12, 0x34, 0xbb], info.clone()))),
+        ]);
+
+        let mut radio = FilteringRadio::new(radio, &[0x12, 0x34], 1, 32);
+        radio.set_option(&ConfigOption::Promiscuous(true)).unwrap();
+        assert!(radio.filtering());
+
+        radio.start_receive().unwrap();
+        assert_eq!(radio.check_receive(true), Ok(false));
+        assert_eq!(radio.check_receive(true), Ok(true));
+
+        let mut buff = [0u8; 32];
+        let (n, i) = radio.get_received(&mut buff).unwrap();
+        assert_eq!(&buff[..n], &[0x00, 0x12, 0x34, 0xbb]);
+        assert_eq!(i, info);
+
+        radio.into_inner().done();
+    }
+
+    #[test]
+    fn test_radio_mock_get_packet_config() {
+        let config = PacketConfig {
+            crc: true,
+            whitening: false,
+            variable_length: true,
+        };
+        let mut radio = MockRadio::new(&[Transaction::get_packet_config(Ok(config))]);
+
+        assert_eq!(radio.get_packet_config(), Ok(config));
+
+        radio.done();
+    }
+
+    #[test]
+    #[cfg(feature = "helpers")]
+    fn test_radio_mock_reliable_link() {
+        use crate::helpers::{ReliableError, ReliableLink};
+
+        let info = BasicInfo::new(-81, 0);
+        let opts = BlockingOptions {
+            poll_interval: Duration::from_micros(100),
+            timeout: Duration::from_micros(100),
+        };
+
+        // Sender retries after a missing ACK
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0x00, 0x00, 0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::check_receive(true, Ok(false)),
+            Transaction::start_transmit(vec![0x00, 0x00, 0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x00, 0x01], info.clone()))),
+        ]);
+        radio.set_permissive_delay(true);
+
+        let mut link = ReliableLink::new(radio, 32, opts.clone());
+        link.send_reliable(&[0xaa], 2).unwrap();
+        link.into_inner().done();
+
+        // Sender queues data frames received while awaiting an ACK
+        let mut radio = MockRadio::new(&[
+            Transaction::start_transmit(vec![0x00, 0x00, 0xaa], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x07, 0x00, 0xcc, 0xdd], info.clone()))),
+            Transaction::start_transmit(vec![0x07, 0x01], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x00, 0x01], info.clone()))),
+        ]);
+        radio.set_permissive_delay(true);
+
+        let mut link = ReliableLink::new(radio, 32, opts.clone());
+        link.send_reliable(&[0xaa], 2).unwrap();
+
+        // Queued payloads are not truncated
+        let mut buff = [0u8; 32];
+        assert_eq!(
+            link.poll_recv(&mut buff[..1]),
+            Err(ReliableError::BufferTooSmall { len: 2 })
+        );
+        assert_eq!(link.poll_recv(&mut buff), Ok(Some((2, info.clone()))));
+        assert_eq!(&buff[..2], &[0xcc, 0xdd]);
+
+        link.into_inner().done();
+
+        // Receiver acknowledges and drops duplicates
+        let mut radio = MockRadio::new(&[
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x05, 0x00, 0xbb], info.clone()))),
+            Transaction::start_transmit(vec![0x05, 0x01], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+            Transaction::check_receive(true, Ok(true)),
+            Transaction::get_received(Ok((vec![0x05, 0x00, 0xbb], info.clone()))),
+            Transaction::start_transmit(vec![0x05, 0x01], None),
+            Transaction::check_transmit(Ok(true)),
+            Transaction::start_receive(None),
+        ]);
+        radio.set_permissive_delay(true);
+
+        let mut link = ReliableLink::new(radio, 32, opts);
+        let mut buff = [0u8; 32];
+
+        link.listen().unwrap();
+        assert_eq!(link.poll_recv(&mut buff), Ok(Some((1, info))));
+        assert_eq!(buff[0], 0xbb);
+        assert_eq!(link.poll_recv(&mut buff), Ok(None));
+
+        link.into_inner().done();
+    }
+
+    #[test]
+    fn test_radio_mock_sensitivity() {
+        let mut radio = MockRadio::new(&[
+            Transaction::sensitivity(Ok(Some(-137))),
+            Transaction::sensitivity(Ok(None)),
+        ]);
+
+        assert_eq!(radio.sensitivity(), Ok(Some(-137)));
+        assert_eq!(radio.sensitivity(), Ok(None));
+
+        radio.done();
+    }
 }
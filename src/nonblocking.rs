@@ -11,9 +11,13 @@ use core::pin::Pin;
 use core::task::{Context, Poll};
 use core::time::Duration;
 
+use crate::blocking::{OptionsError, MIN_POLL_INTERVAL};
 use crate::{Power, Receive, ReceiveInfo, Transmit};
 
+use crate::linktest::{LinkTestInfo, PingPongOptions};
+
 /// Options for async driver calls
+#[derive(Clone)]
 pub struct AsyncOptions {
     /// Power option, for transmit operations
     pub power: Option<i8>,
@@ -41,6 +45,25 @@ impl Default for AsyncOptions {
     }
 }
 
+impl AsyncOptions {
+    /// Create async options with the provided poll period, rejecting a zero period
+    pub fn new(poll_period: Duration) -> Result<Self, OptionsError> {
+        if poll_period.is_zero() {
+            return Err(OptionsError::ZeroPollInterval);
+        }
+
+        Ok(Self {
+            poll_period,
+            ..Default::default()
+        })
+    }
+
+    /// Fetch the poll period, clamped to `MIN_POLL_INTERVAL`
+    pub fn clamped_poll_period(&self) -> Duration {
+        self.poll_period.max(MIN_POLL_INTERVAL)
+    }
+}
+
 /// AsyncError wraps radio errors and provides notification of timeouts
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
@@ -103,9 +126,25 @@ pub trait AsyncTransmit<'a, E> {
 pub struct TransmitFuture<'a, T, E> {
     radio: &'a mut T,
     options: AsyncOptions,
+    polls: u32,
+    #[cfg(feature = "std")]
+    started: std::time::Instant,
     _err: PhantomData<E>,
 }
 
+impl<'a, T, E> TransmitFuture<'a, T, E> {
+    /// Number of times the future has been polled, for debugging stalled executors
+    pub fn poll_count(&self) -> u32 {
+        self.polls
+    }
+
+    /// Time elapsed since the transmission was started
+    #[cfg(feature = "std")]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
 /// `AsyncTransmit` object for all `Transmit` devices
 impl<'a, T, E> AsyncTransmit<'a, E> for T
 where
@@ -131,6 +170,9 @@ where
         let f: TransmitFuture<_, E> = TransmitFuture {
             radio: self,
             options: tx_options,
+            polls: 0,
+            #[cfg(feature = "std")]
+            started: std::time::Instant::now(),
             _err: PhantomData,
         };
 
@@ -147,7 +189,8 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let s = self.get_mut();
-        let period = s.options.poll_period.clone();
+        let period = s.options.clamped_poll_period();
+        s.polls = s.polls.wrapping_add(1);
 
         // Check for completion
         if s.radio.check_transmit()? {
@@ -225,10 +268,26 @@ pub struct ReceiveFuture<'a, T, I, E> {
     radio: &'a mut T,
     buff: &'a mut [u8],
     options: AsyncOptions,
+    polls: u32,
+    #[cfg(feature = "std")]
+    started: std::time::Instant,
     _inf: PhantomData<I>,
     _err: PhantomData<E>,
 }
 
+impl<'a, T, I, E> ReceiveFuture<'a, T, I, E> {
+    /// Number of times the future has been polled, for debugging stalled executors
+    pub fn poll_count(&self) -> u32 {
+        self.polls
+    }
+
+    /// Time elapsed since receive mode was entered
+    #[cfg(feature = "std")]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
 /// Generic implementation of `AsyncReceive` for all `Receive` capable radio devices
 impl<'a, T, I, E> AsyncReceive<'a, I, E> for T
 where
@@ -251,6 +310,9 @@ where
             radio: self,
             buff,
             options: rx_options,
+            polls: 0,
+            #[cfg(feature = "std")]
+            started: std::time::Instant::now(),
             _inf: PhantomData,
             _err: PhantomData,
         };
@@ -269,9 +331,10 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let s = self.get_mut();
+        s.polls = s.polls.wrapping_add(1);
 
         // Check for completion
-        if s.radio.check_receive(true)? {
+        if s.radio.check_receive_continuous()? {
             // Retrieve data
             let (n, i) = s.radio.get_received(s.buff)?;
 
@@ -282,7 +345,7 @@ where
 
         // Execute wake function
         if let Some(w) = s.options.wake_fn {
-            w(cx, s.options.poll_period)
+            w(cx, s.options.clamped_poll_period())
         } else {
             cx.waker().clone().wake();
         }
@@ -292,6 +355,79 @@ where
     }
 }
 
+/// Async link test (ping-pong) implementation, mirroring `helpers::do_ping_pong`
+///
+/// This uses the `AsyncTransmit` and `AsyncReceive` futures, with the provided
+/// `delay` function used for both the inter-round delay and response timeouts.
+pub async fn async_ping_pong<T, I, E, D, F>(
+    radio: &mut T,
+    options: PingPongOptions,
+    async_options: AsyncOptions,
+    mut delay: D,
+) -> Result<LinkTestInfo, AsyncError<E>>
+where
+    T: Receive<Info = I, Error = E> + Transmit<Error = E> + Power<Error = E>,
+    I: ReceiveInfo + Unpin,
+    E: Debug + Unpin,
+    D: FnMut(Duration) -> F,
+    F: Future<Output = ()>,
+{
+    let mut link_info = LinkTestInfo::new(options.rounds);
+
+    let mut buff = [0u8; 32];
+
+    // Set output power if specified
+    if let Some(p) = options.power {
+        radio.set_power(p)?;
+    }
+
+    for i in 0..options.rounds {
+        // Encode message
+        let n = LinkTestInfo::encode_ping(&mut buff, i);
+
+        // Send message
+        radio
+            .async_transmit(&buff[0..n], async_options.clone())?
+            .await?;
+
+        // Await response or timeout
+        let res = {
+            let rx = radio.async_receive(&mut buff, async_options.clone())?;
+            let timeout = delay(options.blocking_options.timeout);
+
+            let mut rx = core::pin::pin!(rx);
+            let mut timeout = core::pin::pin!(timeout);
+
+            core::future::poll_fn(|cx| {
+                if let Poll::Ready(r) = rx.as_mut().poll(cx) {
+                    return Poll::Ready(Some(r));
+                }
+                if timeout.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(None);
+                }
+                Poll::Pending
+            })
+            .await
+        };
+
+        let (n, info) = match res {
+            Some(r) => r?,
+            None => continue,
+        };
+
+        // Update link statistics
+        let parse_info = options.parse_info.then_some(options.info_format);
+        if !link_info.update(i, &buff[0..n], &info, parse_info) {
+            continue;
+        }
+
+        // Wait for send delay
+        delay(options.delay).await;
+    }
+
+    Ok(link_info)
+}
+
 /// Task waker using async_std task::spawn with a task::sleep.
 /// Note that this cannot be relied on for accurate timing
 #[cfg(feature = "async-std")]
@@ -0,0 +1,145 @@
+//! Fixed capacity packet buffers
+//!
+//! `Packet` wraps a `[u8; N]` buffer with length tracking, avoiding passing
+//! `(&mut [u8], usize)` pairs around receive APIs. This is `no_std` compatible.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+use core::fmt;
+
+/// Packet buffer with a fixed capacity of `N` bytes
+#[derive(Clone)]
+pub struct Packet<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Packet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Packet<N> {
+    /// Create a new (empty) packet
+    pub fn new() -> Self {
+        Self {
+            data: [0u8; N],
+            len: 0,
+        }
+    }
+
+    /// Create a packet from a slice, returning None if the slice exceeds the packet capacity
+    pub fn from_slice(data: &[u8]) -> Option<Self> {
+        if data.len() > N {
+            return None;
+        }
+
+        let mut p = Self::new();
+        p.data[..data.len()].copy_from_slice(data);
+        p.len = data.len();
+
+        Some(p)
+    }
+
+    /// Packet capacity in bytes
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Packet length in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the packet is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Fetch packet data
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// Append a byte to the packet, returning the byte if the packet is full
+    pub fn push(&mut self, b: u8) -> Result<(), u8> {
+        if self.len >= N {
+            return Err(b);
+        }
+
+        self.data[self.len] = b;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Clear packet data
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Fetch the full underlying buffer for writing, use `set_len` to update the packet length
+    pub fn buffer_mut(&mut self) -> &mut [u8; N] {
+        &mut self.data
+    }
+
+    /// Set the packet length
+    ///
+    /// Panics if `len` exceeds the packet capacity
+    pub fn set_len(&mut self, len: usize) {
+        assert!(len <= N, "packet length exceeds capacity");
+        self.len = len;
+    }
+}
+
+impl<const N: usize> PartialEq for Packet<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const N: usize> fmt::Debug for Packet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Packet").field(&self.as_slice()).finish()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Packet<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_round_trip() {
+        let data = [0xaa, 0xbb, 0xcc];
+
+        let mut p = Packet::<4>::from_slice(&data).unwrap();
+        assert_eq!(p.len(), 3);
+        assert_eq!(p.as_slice(), &data);
+
+        assert_eq!(p.push(0xdd), Ok(()));
+        assert_eq!(p.push(0xee), Err(0xee));
+        assert_eq!(p.as_slice(), &[0xaa, 0xbb, 0xcc, 0xdd]);
+
+        p.clear();
+        assert!(p.is_empty());
+
+        assert!(Packet::<2>::from_slice(&data).is_none());
+    }
+
+    #[test]
+    fn packet_buffer_set_len() {
+        let mut p = Packet::<8>::new();
+        p.buffer_mut()[..2].copy_from_slice(&[0x01, 0x02]);
+        p.set_len(2);
+
+        assert_eq!(p, Packet::from_slice(&[0x01, 0x02]).unwrap());
+    }
+}
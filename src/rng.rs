@@ -0,0 +1,78 @@
+//! Random number sources for helpers using randomness
+//!
+//! Helpers that require randomness (such as backoff or hopping) accept an `Rng` so
+//! tests can inject a seeded generator while applications use a hardware entropy source.
+//! A small `no_std` xorshift generator is provided for deterministic use.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+/// Random number source
+pub trait Rng {
+    /// Fetch the next random `u32`
+    fn next_u32(&mut self) -> u32;
+
+    /// Fetch a random value in the range `0..max` (returns 0 where `max` is 0)
+    fn next_below(&mut self, max: u32) -> u32 {
+        match max {
+            0 => 0,
+            _ => self.next_u32() % max,
+        }
+    }
+}
+
+/// Xorshift32 pseudo-random number generator
+///
+/// This is deterministic for a given seed and is _not_ suitable for cryptographic use.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    /// Create a new generator with the provided seed (a zero seed is replaced with a fixed non-zero value)
+    pub fn new(seed: u32) -> Self {
+        let state = match seed {
+            0 => 0x2545_f491,
+            s => s,
+        };
+
+        Self { state }
+    }
+}
+
+impl Rng for XorShift32 {
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xorshift_deterministic() {
+        let mut a = XorShift32::new(1234);
+        let mut b = XorShift32::new(1234);
+
+        for _ in 0..16 {
+            let v = a.next_u32();
+            assert_eq!(v, b.next_u32());
+            assert_ne!(v, 0);
+        }
+
+        // Reference values for seed 1
+        let mut r = XorShift32::new(1);
+        assert_eq!(r.next_u32(), 270369);
+        assert_eq!(r.next_u32(), 67634689);
+
+        assert!(a.next_below(10) < 10);
+        assert_eq!(a.next_below(0), 0);
+    }
+}
@@ -0,0 +1,111 @@
+//! Statistics helpers for link testing and diagnostics
+//!
+//! These are `no_std` compatible for use on-device.
+//!
+//! ## <https://github.com/rust-iot/radio-hal>
+//! ## Copyright 2020-2022 Ryan Kurte
+
+/// Running statistics accumulator using Welford's online algorithm
+///
+/// This tracks the min, max, mean, and variance of a sequence of values
+/// without storing the values themselves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunningStats {
+    /// Smallest value seen
+    pub min: f32,
+    /// Largest value seen
+    pub max: f32,
+    /// Mean of values seen
+    pub mean: f32,
+    /// Number of values seen
+    pub count: usize,
+
+    /// Sum of squared differences from the mean
+    m2: f32,
+}
+
+impl Default for RunningStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunningStats {
+    /// Create a new (empty) statistics object
+    pub fn new() -> Self {
+        Self {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            mean: 0.0,
+            count: 0,
+            m2: 0.0,
+        }
+    }
+
+    /// Update statistics with a new value
+    pub fn update(&mut self, value: f32) {
+        if value < self.min {
+            self.min = value;
+        }
+        if value > self.max {
+            self.max = value;
+        }
+
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Sample variance of values seen (zero for fewer than two values)
+    pub fn variance(&self) -> f32 {
+        match self.count {
+            0 | 1 => 0.0,
+            n => self.m2 / (n - 1) as f32,
+        }
+    }
+
+    /// Sample standard deviation of values seen
+    #[cfg(feature = "std")]
+    pub fn std_dev(&self) -> f32 {
+        self.variance().sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_stats() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        let mut stats = RunningStats::new();
+        for v in values.iter() {
+            stats.update(*v);
+        }
+
+        // Reference values using two-pass computation
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let var = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / (n - 1.0);
+
+        assert_eq!(stats.count, values.len());
+        assert_eq!(stats.min, 2.0);
+        assert_eq!(stats.max, 9.0);
+        assert!((stats.mean - mean).abs() < 1e-6);
+        assert!((stats.variance() - var).abs() < 1e-5);
+    }
+
+    #[test]
+    fn running_stats_empty() {
+        let mut stats = RunningStats::new();
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.variance(), 0.0);
+
+        stats.update(-80.0);
+        assert_eq!(stats.mean, -80.0);
+        assert_eq!(stats.variance(), 0.0);
+    }
+}